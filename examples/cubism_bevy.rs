@@ -143,11 +143,15 @@ fn render(
         BUTTON_OUTLINE_THICKNESS,
     );
 
-    for streak in &mut state.streaks {
-        let mut streak = streak.clone();
-        streak.progress = ((STREAK_SPEED * t) + streak.progress) % 1.0;
+    {
+        let mut button = canvas.view(offset_x, offset_y, BUTTON_WIDTH, BUTTON_HEIGHT);
+
+        for streak in &mut state.streaks {
+            let mut streak = streak.clone();
+            streak.progress = ((STREAK_SPEED * t) + streak.progress) % 1.0;
 
-        draw_streak(&mut canvas, offset_x, offset_y, &streak);
+            draw_streak(&mut button, &streak);
+        }
     }
 
     draw_rect_highlight(
@@ -159,7 +163,7 @@ fn render(
         BUTTON_HEIGHT + (BUTTON_OUTLINE_THICKNESS * 2),
     );
 
-    draw_button_content(&mut canvas, offset_x, offset_y, BUTTON_WIDTH, BUTTON_HEIGHT);
+    draw_button_content(&mut canvas.view(offset_x, offset_y, BUTTON_WIDTH, BUTTON_HEIGHT));
 
     {
         // Manually centered
@@ -180,15 +184,20 @@ fn draw_thick_rect_outline(
     h: i32,
     thickness: i32,
 ) {
-    let mut shape = canvas.shape().fill(Color::WHITE).outline(TRANSPARENT);
-
-    shape.rect(x - thickness, y - thickness, x + w + thickness, y);
-    shape.rect(x - thickness, y + h, x + w + thickness, y + h + thickness);
-    shape.rect(x - thickness, y, x, y + h);
-    shape.rect(x + w, y, x + w + thickness, y + h);
+    canvas
+        .shape()
+        .fill(TRANSPARENT)
+        .outline(Color::WHITE)
+        .stroke_width(thickness + 1)
+        .rect(
+            x - thickness,
+            y - thickness,
+            x + w + thickness,
+            y + h + thickness,
+        );
 }
 
-fn draw_streak(canvas: &mut impl Canvas, offset_x: i32, offset_y: i32, streak: &Streak) {
+fn draw_streak(canvas: &mut impl Canvas, streak: &Streak) {
     let mut shape = canvas.shape().fill(TRANSPARENT).outline(Color::WHITE);
 
     let streak_offset: f32 = 0.3;
@@ -204,12 +213,7 @@ fn draw_streak(canvas: &mut impl Canvas, offset_x: i32, offset_y: i32, streak: &
     let ex = x + (STREAK_LENGTH * streak_end);
     let ey = y + (STREAK_LENGTH * streak_end);
 
-    shape.line(
-        offset_x + sx as i32,
-        offset_y + sy as i32,
-        offset_x + ex as i32,
-        offset_y + ey as i32,
-    );
+    shape.line(sx as i32, sy as i32, ex as i32, ey as i32);
 }
 
 fn f(x: f32, t: f32) -> f32 {
@@ -269,14 +273,16 @@ fn draw_rect_highlight(time: f32, canvas: &mut impl Canvas, x: i32, y: i32, w: i
     }
 }
 
-fn draw_button_content(canvas: &mut impl Canvas, x: i32, y: i32, w: i32, h: i32) {
+fn draw_button_content(canvas: &mut impl Canvas) {
+    let (w, h) = canvas.size();
+
     let mut shape = canvas.shape().fill(Color::BLACK).outline(TRANSPARENT);
 
-    let sx = x + CONTENT_OFFSET;
-    let sy = y + CONTENT_OFFSET;
+    let sx = CONTENT_OFFSET;
+    let sy = CONTENT_OFFSET;
 
-    let ex = x + w - CONTENT_OFFSET;
-    let ey = y + h - CONTENT_OFFSET;
+    let ex = w - CONTENT_OFFSET;
+    let ey = h - CONTENT_OFFSET;
 
     shape.rect(sx, sy, ex, ey);
 }
@@ -0,0 +1,154 @@
+/// How color values are stored in a pixel buffer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AlphaMode {
+    /// Color channels are independent of alpha.
+    #[default]
+    Straight,
+    /// Color channels are already multiplied by alpha.
+    Premultiplied,
+}
+
+/// How a drawn color is combined with the color already on the canvas.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BlendMode {
+    /// Overwrites the destination, including its alpha.
+    Replace,
+    /// Regular alpha blending.
+    #[default]
+    SourceOver,
+    /// Adds source and destination, useful for glows and light layers.
+    Additive,
+    /// Darkens the destination, useful for shadows.
+    Multiply,
+    /// Lightens the destination, the inverse of `Multiply`.
+    Screen,
+    /// `Multiply` on dark destination colors, `Screen` on light ones.
+    Overlay,
+    /// Keeps the darker of both colors.
+    Darken,
+    /// Keeps the lighter of both colors.
+    Lighten,
+    /// Porter-Duff xor, keeps only the parts where exactly one side is opaque.
+    Xor,
+    /// Inverts the destination color, weighted by the source alpha. The source color is ignored.
+    Invert,
+}
+
+impl BlendMode {
+    /// Blends `src` onto `dst`, both given as straight `[r, g, b, a]` values.
+    pub fn apply(self, src: [f32; 4], dst: [f32; 4]) -> [f32; 4] {
+        let [sr, sg, sb, sa] = src;
+        let [dr, dg, db, da] = dst;
+
+        match self {
+            BlendMode::Replace => src,
+            BlendMode::SourceOver => source_over(src, dst),
+            BlendMode::Additive => {
+                let a = (sa + da).min(1.0);
+
+                if a <= 0.0 {
+                    return [0.0; 4];
+                }
+
+                let add = |s: f32, d: f32| (s * sa + d * da).min(1.0) / a;
+
+                [add(sr, dr), add(sg, dg), add(sb, db), a]
+            }
+            BlendMode::Multiply => separable(src, dst, |s, d| s * d),
+            BlendMode::Screen => separable(src, dst, screen),
+            BlendMode::Overlay => separable(src, dst, |s, d| {
+                if d <= 0.5 {
+                    2.0 * s * d
+                } else {
+                    screen(s, 2.0 * d - 1.0)
+                }
+            }),
+            BlendMode::Darken => separable(src, dst, f32::min),
+            BlendMode::Lighten => separable(src, dst, f32::max),
+            BlendMode::Xor => {
+                let sw = sa * (1.0 - da);
+                let dw = da * (1.0 - sa);
+                let a = sw + dw;
+
+                if a <= 0.0 {
+                    return [0.0; 4];
+                }
+
+                let xor = |s: f32, d: f32| (s * sw + d * dw) / a;
+
+                [xor(sr, dr), xor(sg, dg), xor(sb, db), a]
+            }
+            BlendMode::Invert => {
+                let invert = |d: f32| d + (1.0 - 2.0 * d) * sa;
+
+                [invert(dr), invert(dg), invert(db), da]
+            }
+        }
+    }
+}
+
+fn screen(s: f32, d: f32) -> f32 {
+    s + d - s * d
+}
+
+/// Composites a separable blend function `f(src, dst)` using source-over for coverage,
+/// as described in the W3C compositing spec.
+fn separable(src: [f32; 4], dst: [f32; 4], f: impl Fn(f32, f32) -> f32) -> [f32; 4] {
+    let [sr, sg, sb, sa] = src;
+    let [dr, dg, db, da] = dst;
+
+    let a = sa + da * (1.0 - sa);
+
+    if a <= 0.0 {
+        return [0.0; 4];
+    }
+
+    let mix = |s: f32, d: f32| (sa * (1.0 - da) * s + sa * da * f(s, d) + (1.0 - sa) * da * d) / a;
+
+    [mix(sr, dr), mix(sg, dg), mix(sb, db), a]
+}
+
+/// Porter-Duff source-over with straight alpha on both sides.
+pub(crate) fn source_over(src: [f32; 4], dst: [f32; 4]) -> [f32; 4] {
+    let [sr, sg, sb, sa] = src;
+    let [dr, dg, db, da] = dst;
+
+    let a = sa + da * (1.0 - sa);
+
+    if a <= 0.0 {
+        return [0.0; 4];
+    }
+
+    let dw = da * (1.0 - sa);
+
+    [
+        (sr * sa + dr * dw) / a,
+        (sg * sa + dg * dw) / a,
+        (sb * sa + db * dw) / a,
+        a,
+    ]
+}
+
+/// Porter-Duff source-over with premultiplied alpha on both sides.
+pub(crate) fn source_over_premultiplied(src: [f32; 4], dst: [f32; 4]) -> [f32; 4] {
+    let inv = 1.0 - src[3];
+
+    [
+        src[0] + dst[0] * inv,
+        src[1] + dst[1] * inv,
+        src[2] + dst[2] * inv,
+        src[3] + dst[3] * inv,
+    ]
+}
+
+pub(crate) fn premultiply([r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    [r * a, g * a, b * a, a]
+}
+
+pub(crate) fn unpremultiply([r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    if a <= 0.0 {
+        return [0.0; 4];
+    }
+
+    [r / a, g / a, b / a, a]
+}
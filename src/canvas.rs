@@ -1,23 +1,68 @@
+use self::flood_fill::FloodFill;
 use self::shape::Shape;
+use self::view::View;
 use crate::Blit;
-use crate::Color;
+use crate::{BlendMode, Color, Rect};
 
 pub mod blit;
+pub mod flood_fill;
 pub mod shape;
+pub mod view;
 
 pub trait Canvas {
-    fn set(&mut self, x: i32, y: i32, color: Color);
+    fn blend(&mut self, x: i32, y: i32, color: Color, mode: BlendMode);
+
+    fn set(&mut self, x: i32, y: i32, color: Color) {
+        self.blend(x, y, color, BlendMode::SourceOver);
+    }
 
     fn size(&self) -> (i32, i32);
 
+    /// Restricts drawing to `rect` (intersected with the current clip) until the matching `pop_clip`.
+    ///
+    /// Canvases without a clip stack ignore it and always draw to their whole area.
+    fn push_clip(&mut self, rect: Rect) {
+        let _ = rect;
+    }
+
+    fn pop_clip(&mut self) {}
+
+    /// The area drawing is currently restricted to.
+    fn clip_rect(&self) -> Rect {
+        Rect::from_size(self.size())
+    }
+
+    /// Blends `color` onto row `y` from `x0` to `x1` inclusive.
+    ///
+    /// Shapes, flood fills and `clear` draw through this and `blend_rect`, so canvases backed
+    /// by memory should override both with something faster than per-pixel `blend`.
+    fn blend_span(&mut self, y: i32, x0: i32, x1: i32, color: Color, mode: BlendMode) {
+        for x in x0..=x1 {
+            self.blend(x, y, color, mode);
+        }
+    }
+
+    /// Same as `blend_span` with `BlendMode::SourceOver`.
+    fn fill_span(&mut self, y: i32, x0: i32, x1: i32, color: Color) {
+        self.blend_span(y, x0, x1, color, BlendMode::SourceOver);
+    }
+
+    /// Blends `color` onto the rectangle between `(x0, y0)` and `(x1, y1)` inclusive.
+    fn blend_rect(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: Color, mode: BlendMode) {
+        for y in y0..=y1 {
+            self.blend_span(y, x0, x1, color, mode);
+        }
+    }
+
+    /// Same as `blend_rect` with `BlendMode::SourceOver`.
+    fn fill_rect(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: Color) {
+        self.blend_rect(x0, y0, x1, y1, color, BlendMode::SourceOver);
+    }
+
     fn clear(&mut self, color: Color) {
         let (width, height) = self.size();
 
-        for x in 0..width {
-            for y in 0..height {
-                self.set(x, y, color);
-            }
-        }
+        self.blend_rect(0, 0, width - 1, height - 1, color, BlendMode::Replace);
     }
 
     fn shape(&mut self) -> Shape<'_, Self>
@@ -33,12 +78,40 @@ pub trait Canvas {
     {
         Blit::new(self)
     }
+
+    /// Returns a canvas covering the given region, translated so that `(x, y)` becomes `(0, 0)`.
+    fn view(&mut self, x: i32, y: i32, w: i32, h: i32) -> View<'_, Self>
+    where
+        Self: Sized,
+    {
+        View::new(self, x, y, w, h)
+    }
 }
 
-impl<'a, T> Canvas for &'a mut T
+/// A canvas whose pixels can be read back.
+pub trait ReadCanvas: Canvas {
+    /// Returns `None` if the position lies outside the canvas.
+    fn get(&self, x: i32, y: i32) -> Option<Color>;
+
+    /// Fills the pixels connected to `(x, y)` that match its color within `tolerance` with `color`.
+    ///
+    /// Nothing is drawn until `finish` is called on the returned `FloodFill`.
+    fn flood_fill(&mut self, x: i32, y: i32, color: Color, tolerance: f32) -> FloodFill<'_, Self>
+    where
+        Self: Sized,
+    {
+        FloodFill::new(self, x, y, color, tolerance)
+    }
+}
+
+impl<T> Canvas for &mut T
 where
     T: Canvas,
 {
+    fn blend(&mut self, x: i32, y: i32, color: Color, mode: BlendMode) {
+        T::blend(self, x, y, color, mode);
+    }
+
     fn set(&mut self, x: i32, y: i32, color: Color) {
         T::set(self, x, y, color);
     }
@@ -46,4 +119,45 @@ where
     fn size(&self) -> (i32, i32) {
         T::size(self)
     }
+
+    fn push_clip(&mut self, rect: Rect) {
+        T::push_clip(self, rect);
+    }
+
+    fn pop_clip(&mut self) {
+        T::pop_clip(self);
+    }
+
+    fn clip_rect(&self) -> Rect {
+        T::clip_rect(self)
+    }
+
+    fn blend_span(&mut self, y: i32, x0: i32, x1: i32, color: Color, mode: BlendMode) {
+        T::blend_span(self, y, x0, x1, color, mode);
+    }
+
+    fn fill_span(&mut self, y: i32, x0: i32, x1: i32, color: Color) {
+        T::fill_span(self, y, x0, x1, color);
+    }
+
+    fn blend_rect(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: Color, mode: BlendMode) {
+        T::blend_rect(self, x0, y0, x1, y1, color, mode);
+    }
+
+    fn fill_rect(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: Color) {
+        T::fill_rect(self, x0, y0, x1, y1, color);
+    }
+
+    fn clear(&mut self, color: Color) {
+        T::clear(self, color);
+    }
+}
+
+impl<T> ReadCanvas for &mut T
+where
+    T: ReadCanvas,
+{
+    fn get(&self, x: i32, y: i32) -> Option<Color> {
+        T::get(self, x, y)
+    }
 }
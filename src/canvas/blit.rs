@@ -1,4 +1,7 @@
-use crate::{Canvas, Color};
+use std::f32::consts::FRAC_PI_2;
+
+use crate::blend;
+use crate::{BlendMode, Canvas, Color, Image, Rect};
 
 pub struct Blit<'a, C> {
     canvas: &'a mut C,
@@ -11,6 +14,28 @@ pub struct Blit<'a, C> {
     img_data: &'a [u8],
     img_stride: usize,
     mul_color: Color,
+    blend: BlendMode,
+    scale: (f32, f32),
+    dst_size: Option<(i32, i32)>,
+    filter: Filter,
+    transform: Transform,
+}
+
+/// How pixels are picked from the image when it's drawn at a different size.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Filter {
+    /// Takes the closest pixel, keeping pixel art crisp at whole number scales.
+    #[default]
+    Nearest,
+    /// Blends the four closest pixels, for smooth results at any scale.
+    Bilinear,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Transform {
+    None,
+    Rotate { angle: f32, pivot: (f32, f32) },
+    Matrix([f32; 6]),
 }
 
 #[cfg(feature = "bevy_ext")]
@@ -47,6 +72,11 @@ impl<'a, C> Blit<'a, C> {
             img_data: &[],
             img_stride: 0,
             mul_color: Color::WHITE,
+            blend: BlendMode::SourceOver,
+            scale: (1.0, 1.0),
+            dst_size: None,
+            filter: Filter::Nearest,
+            transform: Transform::None,
         }
     }
 
@@ -60,6 +90,16 @@ impl<'a, C> Blit<'a, C> {
         self
     }
 
+    pub fn image(mut self, image: &'a Image) -> Self {
+        self.img_data = image.pixels();
+        self.img_stride = image.width() as usize;
+
+        self.src_w = image.width();
+        self.src_h = image.height();
+
+        self
+    }
+
     pub fn image_clip(
         mut self,
         img_data: &'a [u8],
@@ -84,11 +124,64 @@ impl<'a, C> Blit<'a, C> {
         self
     }
 
+    pub fn blend(mut self, mode: BlendMode) -> Self {
+        self.blend = mode;
+        self
+    }
+
     pub fn pos(mut self, dst_x: i32, dst_y: i32) -> Self {
         self.dst_x = dst_x;
         self.dst_y = dst_y;
         self
     }
+
+    /// Draws the image `sx` times as wide and `sy` times as tall.
+    pub fn scale(mut self, sx: f32, sy: f32) -> Self {
+        self.scale = (sx, sy);
+        self.dst_size = None;
+        self
+    }
+
+    /// Stretches the image to `w` by `h` pixels, replacing any `scale`.
+    pub fn dest_size(mut self, w: i32, h: i32) -> Self {
+        self.dst_size = Some((w, h));
+        self
+    }
+
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Rotates the image clockwise by `angle` radians around `(pivot_x, pivot_y)`, in pixels
+    /// of the image, which stays where it would be without the rotation.
+    ///
+    /// Quarter turns move whole pixels, so they stay exact. Replaces any `transform`.
+    pub fn rotate(mut self, angle: f32, pivot_x: f32, pivot_y: f32) -> Self {
+        self.transform = Transform::Rotate {
+            angle,
+            pivot: (pivot_x, pivot_y),
+        };
+        self
+    }
+
+    /// Maps the scaled image onto the canvas with the affine matrix `[a, b, c, d, e, f]`,
+    /// as `x' = a * x + c * y + e` and `y' = b * x + d * y + f`, relative to `pos`.
+    ///
+    /// Replaces any `rotate`.
+    pub fn transform(mut self, matrix: [f32; 6]) -> Self {
+        self.transform = Transform::Matrix(matrix);
+        self
+    }
+
+    fn dest_size_or_scaled(&self) -> (i32, i32) {
+        self.dst_size.unwrap_or_else(|| {
+            let w = (self.src_w as f32 * self.scale.0).round() as i32;
+            let h = (self.src_h as f32 * self.scale.1).round() as i32;
+
+            (w, h)
+        })
+    }
 }
 
 impl<'a, C> Blit<'a, C>
@@ -96,6 +189,25 @@ where
     C: Canvas,
 {
     pub fn finish(self) {
+        let (dst_w, dst_h) = self.dest_size_or_scaled();
+
+        match self.transform {
+            Transform::None => {}
+            Transform::Rotate { angle, pivot } => {
+                self.finish_rotated(angle, pivot, dst_w, dst_h);
+                return;
+            }
+            Transform::Matrix(matrix) => {
+                self.finish_transformed(matrix, dst_w, dst_h);
+                return;
+            }
+        }
+
+        if (dst_w, dst_h) != (self.src_w, self.src_h) {
+            self.finish_scaled(dst_w, dst_h);
+            return;
+        }
+
         let sampler = BlitSampler::new(self.img_data, self.img_stride as i32);
 
         let mul_color = self.mul_color.as_rgba_f32();
@@ -111,12 +223,247 @@ where
                 let color = sampler.sample(src_x, src_y);
                 let color = color * mul_color;
 
-                self.canvas.set(dst_x, dst_y, color);
+                self.canvas.blend(dst_x, dst_y, color, self.blend);
+            }
+        }
+    }
+
+    /// Draws the image stretched to `dst_w` by `dst_h`, sampling it at the center of every
+    /// destination pixel.
+    fn finish_scaled(self, dst_w: i32, dst_h: i32) {
+        if dst_w <= 0 || dst_h <= 0 || self.src_w <= 0 || self.src_h <= 0 {
+            return;
+        }
+
+        let sampler = BlitSampler::new(self.img_data, self.img_stride as i32);
+        let src_rect = Rect::new(self.src_x, self.src_y, self.src_w, self.src_h);
+
+        let mul_color = self.mul_color.as_rgba_f32();
+
+        let area =
+            Rect::new(self.dst_x, self.dst_y, dst_w, dst_h).intersect(self.canvas.clip_rect());
+
+        for y in area.y..area.y + area.h {
+            for x in area.x..area.x + area.w {
+                let (dx, dy) = (x - self.dst_x, y - self.dst_y);
+                let color = self.scaled_pixel(&sampler, src_rect, dx, dy, dst_w, dst_h);
+
+                self.canvas.blend(x, y, color * mul_color, self.blend);
+            }
+        }
+    }
+
+    fn finish_rotated(self, angle: f32, pivot: (f32, f32), dst_w: i32, dst_h: i32) {
+        if self.src_w <= 0 || self.src_h <= 0 {
+            return;
+        }
+
+        // The pivot moves along with the scaled image
+        let pivot = (
+            pivot.0 * dst_w as f32 / self.src_w as f32,
+            pivot.1 * dst_h as f32 / self.src_h as f32,
+        );
+
+        let turns = angle / FRAC_PI_2;
+
+        if (turns - turns.round()).abs() < 1e-4 {
+            self.finish_quarter_turns(turns.round() as i32, pivot, dst_w, dst_h);
+            return;
+        }
+
+        let (sin, cos) = angle.sin_cos();
+        self.finish_transformed(rotation(sin, cos, pivot), dst_w, dst_h);
+    }
+
+    /// Rotates by whole quarter turns by moving pixels around, snapping the result to the pixel grid.
+    fn finish_quarter_turns(self, turns: i32, pivot: (f32, f32), dst_w: i32, dst_h: i32) {
+        if dst_w <= 0 || dst_h <= 0 {
+            return;
+        }
+
+        let turns = turns.rem_euclid(4);
+        let (sin, cos) = [(0.0, 1.0), (1.0, 0.0), (0.0, -1.0), (-1.0, 0.0)][turns as usize];
+
+        let bounds = transformed_bounds(rotation(sin, cos, pivot), dst_w, dst_h);
+        let left = self.dst_x + bounds.0.round() as i32;
+        let top = self.dst_y + bounds.1.round() as i32;
+
+        let (w, h) = if turns % 2 == 0 {
+            (dst_w, dst_h)
+        } else {
+            (dst_h, dst_w)
+        };
+
+        let sampler = BlitSampler::new(self.img_data, self.img_stride as i32);
+        let src_rect = Rect::new(self.src_x, self.src_y, self.src_w, self.src_h);
+
+        let mul_color = self.mul_color.as_rgba_f32();
+
+        let area = Rect::new(left, top, w, h).intersect(self.canvas.clip_rect());
+
+        for y in area.y..area.y + area.h {
+            for x in area.x..area.x + area.w {
+                let (i, j) = (x - left, y - top);
+
+                let (dx, dy) = match turns {
+                    0 => (i, j),
+                    1 => (j, dst_h - 1 - i),
+                    2 => (dst_w - 1 - i, dst_h - 1 - j),
+                    _ => (dst_w - 1 - j, i),
+                };
+
+                let color = self.scaled_pixel(&sampler, src_rect, dx, dy, dst_w, dst_h);
+
+                self.canvas.blend(x, y, color * mul_color, self.blend);
+            }
+        }
+    }
+
+    /// Draws the scaled image through an affine matrix, mapping the center of every canvas pixel
+    /// in its bounds back onto the image.
+    fn finish_transformed(self, matrix: [f32; 6], dst_w: i32, dst_h: i32) {
+        if dst_w <= 0 || dst_h <= 0 || self.src_w <= 0 || self.src_h <= 0 {
+            return;
+        }
+
+        let Some(inverse) = invert(matrix) else {
+            return;
+        };
+
+        let sampler = BlitSampler::new(self.img_data, self.img_stride as i32);
+        let src_rect = Rect::new(self.src_x, self.src_y, self.src_w, self.src_h);
+
+        let mul_color = self.mul_color.as_rgba_f32();
+
+        let (min_x, min_y, max_x, max_y) = transformed_bounds(matrix, dst_w, dst_h);
+        let left = self.dst_x + min_x.floor() as i32;
+        let top = self.dst_y + min_y.floor() as i32;
+        let right = self.dst_x + max_x.ceil() as i32;
+        let bottom = self.dst_y + max_y.ceil() as i32;
+
+        let area =
+            Rect::new(left, top, right - left, bottom - top).intersect(self.canvas.clip_rect());
+
+        // Scaled image pixels to image pixels
+        let to_src_x = self.src_w as f32 / dst_w as f32;
+        let to_src_y = self.src_h as f32 / dst_h as f32;
+
+        for y in area.y..area.y + area.h {
+            for x in area.x..area.x + area.w {
+                let px = (x - self.dst_x) as f32 + 0.5;
+                let py = (y - self.dst_y) as f32 + 0.5;
+
+                let (u, v) = apply(inverse, px, py);
+
+                if u < 0.0 || v < 0.0 || u >= dst_w as f32 || v >= dst_h as f32 {
+                    continue;
+                }
+
+                let (u, v) = (u * to_src_x, v * to_src_y);
+
+                let color = match self.filter {
+                    Filter::Nearest => {
+                        let src_x = self.src_x + (u as i32).min(self.src_w - 1);
+                        let src_y = self.src_y + (v as i32).min(self.src_h - 1);
+
+                        sampler.sample(src_x, src_y)
+                    }
+                    Filter::Bilinear => sampler.bilinear(
+                        src_rect,
+                        self.src_x as f32 + u - 0.5,
+                        self.src_y as f32 + v - 0.5,
+                    ),
+                };
+
+                self.canvas.blend(x, y, color * mul_color, self.blend);
+            }
+        }
+    }
+
+    /// The color of pixel `(dx, dy)` of the image stretched to `dst_w` by `dst_h`.
+    fn scaled_pixel(
+        &self,
+        sampler: &BlitSampler,
+        src_rect: Rect,
+        dx: i32,
+        dy: i32,
+        dst_w: i32,
+        dst_h: i32,
+    ) -> Color {
+        match self.filter {
+            Filter::Nearest => {
+                let src_x = self.src_x + nearest(dx, self.src_w, dst_w);
+                let src_y = self.src_y + nearest(dy, self.src_h, dst_h);
+
+                sampler.sample(src_x, src_y)
+            }
+            Filter::Bilinear => {
+                let src_x =
+                    self.src_x as f32 + (dx as f32 + 0.5) * self.src_w as f32 / dst_w as f32;
+                let src_y =
+                    self.src_y as f32 + (dy as f32 + 0.5) * self.src_h as f32 / dst_h as f32;
+
+                sampler.bilinear(src_rect, src_x - 0.5, src_y - 0.5)
             }
         }
     }
 }
 
+/// A clockwise rotation around `pivot` as an affine matrix.
+fn rotation(sin: f32, cos: f32, pivot: (f32, f32)) -> [f32; 6] {
+    let (x, y) = pivot;
+
+    [
+        cos,
+        sin,
+        -sin,
+        cos,
+        x - cos * x + sin * y,
+        y - sin * x - cos * y,
+    ]
+}
+
+fn apply([a, b, c, d, e, f]: [f32; 6], x: f32, y: f32) -> (f32, f32) {
+    (a * x + c * y + e, b * x + d * y + f)
+}
+
+fn invert([a, b, c, d, e, f]: [f32; 6]) -> Option<[f32; 6]> {
+    let det = a * d - b * c;
+
+    if det.abs() <= f32::EPSILON {
+        return None;
+    }
+
+    Some([
+        d / det,
+        -b / det,
+        -c / det,
+        a / det,
+        (c * f - d * e) / det,
+        (b * e - a * f) / det,
+    ])
+}
+
+/// The smallest and largest x and y of a `w` by `h` rect mapped through `matrix`.
+fn transformed_bounds(matrix: [f32; 6], w: i32, h: i32) -> (f32, f32, f32, f32) {
+    let (w, h) = (w as f32, h as f32);
+    let corners = [(0.0, 0.0), (w, 0.0), (0.0, h), (w, h)].map(|(x, y)| apply(matrix, x, y));
+
+    corners.iter().fold(
+        (f32::MAX, f32::MAX, f32::MIN, f32::MIN),
+        |(min_x, min_y, max_x, max_y), &(x, y)| {
+            (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+        },
+    )
+}
+
+/// The source pixel under the center of destination pixel `d` when `src` pixels are stretched over `dst`.
+///
+/// Integer math keeps whole number upscales exact.
+fn nearest(d: i32, src: i32, dst: i32) -> i32 {
+    ((2 * d as i64 + 1) * src as i64 / (2 * dst as i64)) as i32
+}
+
 struct BlitSampler<'a> {
     img_data: &'a [u8],
     stride: i32,
@@ -141,4 +488,29 @@ impl<'a> BlitSampler<'a> {
 
         Color::rgba_u8(r, g, b, a)
     }
+
+    /// Blends the four pixels around `(x, y)`, repeating the edge pixels of `rect` outside of it.
+    fn bilinear(&self, rect: Rect, x: f32, y: f32) -> Color {
+        let (max_x, max_y) = (rect.x + rect.w - 1, rect.y + rect.h - 1);
+
+        let x = x.clamp(rect.x as f32, max_x as f32);
+        let y = y.clamp(rect.y as f32, max_y as f32);
+
+        let (x0, y0) = (x.floor() as i32, y.floor() as i32);
+        let (x1, y1) = ((x0 + 1).min(max_x), (y0 + 1).min(max_y));
+        let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+
+        // Mix premultiplied so transparent pixels don't bleed their color into the edges
+        let [tl, tr, bl, br] = [(x0, y0), (x1, y0), (x0, y1), (x1, y1)]
+            .map(|(x, y)| blend::premultiply(self.sample(x, y).as_rgba_f32()));
+
+        let [r, g, b, a] = blend::unpremultiply(std::array::from_fn(|i| {
+            let top = tl[i] + (tr[i] - tl[i]) * fx;
+            let bottom = bl[i] + (br[i] - bl[i]) * fx;
+
+            top + (bottom - top) * fy
+        }));
+
+        Color::rgba(r, g, b, a)
+    }
 }
@@ -0,0 +1,158 @@
+use crate::{BlendMode, Color, ReadCanvas, Rect};
+
+/// Fills the area connected to a starting pixel, like a paint bucket.
+///
+/// By default the area is every pixel reachable through pixels of the starting pixel's color,
+/// `boundary` instead fills everything up to a border of another color.
+pub struct FloodFill<'a, C> {
+    canvas: &'a mut C,
+    x: i32,
+    y: i32,
+    color: Color,
+    tolerance: f32,
+    connectivity: Connectivity,
+    boundary: Option<Color>,
+    blend: BlendMode,
+}
+
+/// Which neighbours of a pixel the fill spreads to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Only the pixels sharing an edge.
+    #[default]
+    Four,
+    /// Pixels sharing a corner as well, so the fill leaks through diagonal gaps.
+    Eight,
+}
+
+impl<'a, C> FloodFill<'a, C> {
+    /// Fills around `(x, y)` with `color`.
+    ///
+    /// Pixels match when none of their channels differ by more than `tolerance`, between 0 and 1.
+    pub fn new(canvas: &'a mut C, x: i32, y: i32, color: Color, tolerance: f32) -> Self {
+        Self {
+            canvas,
+            x,
+            y,
+            color,
+            tolerance,
+            connectivity: Connectivity::Four,
+            boundary: None,
+            blend: BlendMode::SourceOver,
+        }
+    }
+
+    pub fn connectivity(mut self, connectivity: Connectivity) -> Self {
+        self.connectivity = connectivity;
+        self
+    }
+
+    /// Fills every connected pixel that doesn't match `color`, stopping at the ones that do.
+    pub fn boundary(mut self, color: Color) -> Self {
+        self.boundary = Some(color);
+        self
+    }
+
+    pub fn blend(mut self, mode: BlendMode) -> Self {
+        self.blend = mode;
+        self
+    }
+}
+
+impl<'a, C> FloodFill<'a, C>
+where
+    C: ReadCanvas,
+{
+    pub fn finish(self) {
+        let area = self
+            .canvas
+            .clip_rect()
+            .intersect(Rect::from_size(self.canvas.size()));
+
+        if !area.contains(self.x, self.y) {
+            return;
+        }
+
+        let Some(seed) = self.canvas.get(self.x, self.y) else {
+            return;
+        };
+
+        let tolerance = self.tolerance;
+        let boundary = self.boundary;
+
+        let inside = |color: Color| match boundary {
+            Some(boundary) => !matches(color, boundary, tolerance),
+            None => matches(color, seed, tolerance),
+        };
+
+        if !inside(seed) {
+            return;
+        }
+
+        // Filled pixels can still look like the inside, so they're remembered separately
+        let mut filled = vec![false; (area.w * area.h) as usize];
+        let index = |x: i32, y: i32| ((y - area.y) * area.w + x - area.x) as usize;
+
+        let reach = match self.connectivity {
+            Connectivity::Four => 0,
+            Connectivity::Eight => 1,
+        };
+
+        let mut seeds = vec![(self.x, self.y)];
+
+        while let Some((x, y)) = seeds.pop() {
+            if filled[index(x, y)] {
+                continue;
+            }
+
+            // Grow the seed into the whole run of inside pixels on its row
+            let is_open = |x: i32, y: i32, filled: &[bool]| {
+                !filled[index(x, y)] && self.canvas.get(x, y).is_some_and(inside)
+            };
+
+            let mut x0 = x;
+            while x0 > area.x && is_open(x0 - 1, y, &filled) {
+                x0 -= 1;
+            }
+
+            let mut x1 = x;
+            while x1 < area.x + area.w - 1 && is_open(x1 + 1, y, &filled) {
+                x1 += 1;
+            }
+
+            for x in x0..=x1 {
+                filled[index(x, y)] = true;
+            }
+
+            // Queue one seed per run of inside pixels touching it in the rows above and below
+            let scan_x0 = (x0 - reach).max(area.x);
+            let scan_x1 = (x1 + reach).min(area.x + area.w - 1);
+
+            for ny in [y - 1, y + 1] {
+                if ny < area.y || ny >= area.y + area.h {
+                    continue;
+                }
+
+                let mut in_run = false;
+
+                for nx in scan_x0..=scan_x1 {
+                    let open = is_open(nx, ny, &filled);
+
+                    if open && !in_run {
+                        seeds.push((nx, ny));
+                    }
+
+                    in_run = open;
+                }
+            }
+
+            self.canvas.blend_span(y, x0, x1, self.color, self.blend);
+        }
+    }
+}
+
+fn matches(a: Color, b: Color, tolerance: f32) -> bool {
+    let (a, b) = (a.as_rgba_f32(), b.as_rgba_f32());
+
+    (0..4).all(|i| (a[i] - b[i]).abs() <= tolerance)
+}
@@ -1,35 +1,185 @@
-use crate::{Canvas, Color};
+use self::dash::Dash;
+use crate::{Anchor, BlendMode, Canvas, Color, Paint};
+
+mod antialias;
+mod curve;
+mod dash;
+mod ellipse;
+mod path;
+mod polygon;
+mod rounded_rect;
+mod stroke;
+mod triangle;
+
+pub use self::path::Path;
+pub use self::polygon::FillRule;
+pub use self::stroke::{LineCap, LineJoin};
 
 pub struct Shape<'a, C> {
     canvas: &'a mut C,
-    fill: Color,
-    outline: Color,
+    fill: Paint,
+    outline: Paint,
+    blend: BlendMode,
+    fill_rule: FillRule,
+    stroke_width: i32,
+    line_cap: LineCap,
+    line_join: LineJoin,
+    antialias: bool,
+    dash: Vec<i32>,
+    dash_offset: i32,
+    origin: (i32, i32),
 }
 
 impl<'a, C> Shape<'a, C> {
     pub fn new(canvas: &'a mut C) -> Self {
         Self {
             canvas,
-            fill: Color::WHITE,
-            outline: Color::rgba(0.0, 0.0, 0.0, 0.0),
+            fill: Paint::Solid(Color::WHITE),
+            outline: Paint::Solid(Color::rgba(0.0, 0.0, 0.0, 0.0)),
+            blend: BlendMode::SourceOver,
+            fill_rule: FillRule::NonZero,
+            stroke_width: 1,
+            line_cap: LineCap::Butt,
+            line_join: LineJoin::Miter,
+            antialias: false,
+            dash: Vec::new(),
+            dash_offset: 0,
+            origin: (0, 0),
         }
     }
-    pub fn fill(mut self, color: Color) -> Self {
-        self.fill = color;
+
+    /// What the inside of shapes is filled with, either a `Color`, a `Gradient` or a `Pattern`.
+    pub fn fill(mut self, paint: impl Into<Paint>) -> Self {
+        self.fill = paint.into();
+        self
+    }
+
+    pub fn set_fill(&mut self, paint: impl Into<Paint>) {
+        self.fill = paint.into();
+    }
+
+    /// What lines and the outlines of shapes are drawn with, either a `Color`, a `Gradient` or a `Pattern`.
+    pub fn outline(mut self, paint: impl Into<Paint>) -> Self {
+        self.outline = paint.into();
+        self
+    }
+
+    pub fn set_outline(&mut self, paint: impl Into<Paint>) {
+        self.outline = paint.into();
+    }
+
+    pub fn blend(mut self, mode: BlendMode) -> Self {
+        self.blend = mode;
+        self
+    }
+
+    pub fn set_blend(&mut self, mode: BlendMode) {
+        self.blend = mode;
+    }
+
+    pub fn fill_rule(mut self, rule: FillRule) -> Self {
+        self.fill_rule = rule;
+        self
+    }
+
+    pub fn set_fill_rule(&mut self, rule: FillRule) {
+        self.fill_rule = rule;
+    }
+
+    /// Width of outlines and lines in pixels.
+    ///
+    /// Outlines of rects, circles and ellipses grow inwards, lines and polygon outlines
+    /// are centered on their path.
+    pub fn stroke_width(mut self, width: i32) -> Self {
+        self.stroke_width = width;
+        self
+    }
+
+    pub fn set_stroke_width(&mut self, width: i32) {
+        self.stroke_width = width;
+    }
+
+    pub fn line_cap(mut self, cap: LineCap) -> Self {
+        self.line_cap = cap;
+        self
+    }
+
+    pub fn set_line_cap(&mut self, cap: LineCap) {
+        self.line_cap = cap;
+    }
+
+    pub fn line_join(mut self, join: LineJoin) -> Self {
+        self.line_join = join;
+        self
+    }
+
+    pub fn set_line_join(&mut self, join: LineJoin) {
+        self.line_join = join;
+    }
+
+    /// Smooth edges by blending partially covered pixels with a fraction of the color's alpha.
+    ///
+    /// Lines are drawn with Xiaolin Wu's algorithm, everything else from the area each pixel covers.
+    pub fn antialias(mut self, enabled: bool) -> Self {
+        self.antialias = enabled;
+        self
+    }
+
+    pub fn set_antialias(&mut self, enabled: bool) {
+        self.antialias = enabled;
+    }
+
+    /// Alternating on and off lengths in pixels for lines, outlines and polylines.
+    ///
+    /// An empty pattern draws them solid.
+    pub fn dash(mut self, pattern: &[i32]) -> Self {
+        self.set_dash(pattern);
         self
     }
 
-    pub fn set_fill(&mut self, color: Color) {
-        self.fill = color;
+    pub fn set_dash(&mut self, pattern: &[i32]) {
+        self.dash = pattern.to_vec();
     }
 
-    pub fn outline(mut self, color: Color) -> Self {
-        self.outline = color;
+    /// How far into the dash pattern paths start, increase it over time for marching ants.
+    pub fn dash_offset(mut self, offset: i32) -> Self {
+        self.dash_offset = offset;
         self
     }
 
-    pub fn set_outline(&mut self, color: Color) {
-        self.outline = color;
+    pub fn set_dash_offset(&mut self, offset: i32) {
+        self.dash_offset = offset;
+    }
+
+    fn dash_pattern(&self) -> Option<Dash> {
+        Dash::new(&self.dash, self.dash_offset)
+    }
+
+    fn paint(&self, layer: Layer) -> &Paint {
+        match layer {
+            Layer::Fill => &self.fill,
+            Layer::Outline => &self.outline,
+        }
+    }
+
+    /// The color of the pixel at `(x, y)`, moved along with the shape for shape anchored patterns.
+    fn color_at(&self, layer: Layer, x: i32, y: i32) -> Color {
+        let paint = self.paint(layer);
+
+        let (x, y) = match paint.anchor() {
+            Anchor::Canvas => (x, y),
+            Anchor::Shape => (x - self.origin.0, y - self.origin.1),
+        };
+
+        paint.color_at(x as f32, y as f32)
+    }
+
+    /// Anchors patterns to the top left corner of the bounds of `points`.
+    fn set_origin(&mut self, points: impl IntoIterator<Item = (i32, i32)>) {
+        self.origin = points
+            .into_iter()
+            .reduce(|a, b| (a.0.min(b.0), a.1.min(b.1)))
+            .unwrap_or((0, 0));
     }
 }
 
@@ -37,92 +187,166 @@ impl<'a, C> Shape<'a, C>
 where
     C: Canvas,
 {
-    pub fn rect(&mut self, x1: i32, y1: i32, x2: i32, y2: i32) {
-        for x in x1..=x2 {
-            for y in y1..=y2 {
-                self.canvas.set(x, y, self.fill);
-            }
-        }
+    fn paint_pixel(&mut self, layer: Layer, x: i32, y: i32) {
+        let color = self.color_at(layer, x, y);
 
-        if self.outline == self.fill {
+        self.canvas.blend(x, y, color, self.blend);
+    }
+
+    /// Paints row `y` from `x0` to `x1` inclusive.
+    fn paint_span(&mut self, layer: Layer, y: i32, x0: i32, x1: i32) {
+        if let Some(color) = self.paint(layer).as_solid() {
+            self.canvas.blend_span(y, x0, x1, color, self.blend);
             return;
         }
 
-        if is_transparent(&self.outline) {
+        let clip = self.canvas.clip_rect();
+
+        if y < clip.y || y >= clip.y + clip.h {
             return;
         }
 
-        for x in x1..=x2 {
-            self.canvas.set(x, y1, self.outline);
-            self.canvas.set(x, y2, self.outline);
+        for x in x0.max(clip.x)..=x1.min(clip.x + clip.w - 1) {
+            self.paint_pixel(layer, x, y);
         }
+    }
+
+    /// Paints the rectangle between `(x0, y0)` and `(x1, y1)` inclusive.
+    fn paint_rect(&mut self, layer: Layer, x0: i32, y0: i32, x1: i32, y1: i32) {
+        if let Some(color) = self.paint(layer).as_solid() {
+            self.canvas.blend_rect(x0, y0, x1, y1, color, self.blend);
+            return;
+        }
+
+        let clip = self.canvas.clip_rect();
 
-        for y in y1..=y2 {
-            self.canvas.set(x1, y, self.outline);
-            self.canvas.set(x2, y, self.outline);
+        for y in y0.max(clip.y)..=y1.min(clip.y + clip.h - 1) {
+            self.paint_span(layer, y, x0, x1);
         }
     }
 
-    pub fn line(&mut self, mut x1: i32, mut y1: i32, x2: i32, y2: i32) {
-        let dx = (x2 - x1).abs();
-        let dy = (y2 - y1).abs();
+    pub fn rect(&mut self, x1: i32, y1: i32, x2: i32, y2: i32) {
+        self.set_origin([(x1, y1), (x2, y2)]);
 
-        let sx = if x1 < x2 { 1 } else { -1 };
-        let sy = if y1 < y2 { 1 } else { -1 };
+        if self.outline == self.fill || is_transparent(&self.outline) {
+            self.paint_rect(Layer::Fill, x1, y1, x2, y2);
+            return;
+        }
 
-        let mut err = dx - dy;
+        let width = self.stroke_width.max(1);
 
-        loop {
-            self.canvas.set(x1, y1, self.outline);
+        // The outline is a band along the inside of the rect
+        let top = (y1 + width - 1).min(y2);
+        let bottom = (y2 - width + 1).max(top + 1);
+        let left = (x1 + width - 1).min(x2);
+        let right = (x2 - width + 1).max(left + 1);
 
-            if x1 == x2 && y1 == y2 {
-                break;
-            }
+        if self.dash_pattern().is_some() {
+            self.paint_rect(Layer::Fill, left + 1, top + 1, right - 1, bottom - 1);
 
-            let e2 = 2 * err;
+            // Run along the middle of the band
+            let inset = (width - 1) as f32 / 2.0;
+            let (x1, y1) = (x1 as f32 + inset, y1 as f32 + inset);
+            let (x2, y2) = (x2 as f32 - inset, y2 as f32 - inset);
 
-            if e2 > -dy {
-                err -= dy;
-                x1 += sx;
-            }
+            self.stroke_path(&[(x1, y1), (x2, y1), (x2, y2), (x1, y2)], true);
+            return;
+        }
 
-            if e2 < dx {
-                err += dx;
-                y1 += sy;
-            }
+        self.paint_rect(Layer::Outline, x1, y1, x2, top);
+        self.paint_rect(Layer::Outline, x1, bottom, x2, y2);
+        self.paint_rect(Layer::Outline, x1, top + 1, left, bottom - 1);
+        self.paint_rect(Layer::Outline, right, top + 1, x2, bottom - 1);
+
+        self.paint_rect(Layer::Fill, left + 1, top + 1, right - 1, bottom - 1);
+    }
+
+    pub fn line(&mut self, x1: i32, y1: i32, x2: i32, y2: i32) {
+        self.set_origin([(x1, y1), (x2, y2)]);
+
+        if self.stroke_width > 1 {
+            self.stroke(&[(x1, y1), (x2, y2)], false);
+            return;
         }
+
+        let dash = self.dash_pattern();
+
+        line_pixels(self.antialias, x1, y1, x2, y2, |x, y, coverage| {
+            let distance = line_distance(x1, y1, x2, y2, x, y);
+
+            if dash
+                .as_ref()
+                .map_or(true, |dash| dash.is_on(distance as f32))
+            {
+                self.blend_coverage(x, y, Layer::Outline, coverage);
+            }
+        });
+    }
+}
+
+/// How many steps along a line from `(x1, y1)` to `(x2, y2)` the pixel at `(x, y)` is.
+fn line_distance(x1: i32, y1: i32, x2: i32, y2: i32, x: i32, y: i32) -> i32 {
+    if (x2 - x1).abs() >= (y2 - y1).abs() {
+        (x - x1).abs()
+    } else {
+        (y - y1).abs()
     }
+}
 
-    // TODO: This iterates over all image pixels, which is slow
-    //       can be optimized to only iterate over the potential circle pixels (bound by a square)
-    pub fn circle(&mut self, x: i32, y: i32, r: i32) {
-        let r2 = r * r;
+/// Calls `f(x, y, coverage)` for every pixel of a line, antialiased or not.
+fn line_pixels(
+    antialias: bool,
+    x1: i32,
+    y1: i32,
+    x2: i32,
+    y2: i32,
+    mut f: impl FnMut(i32, i32, f32),
+) {
+    if antialias {
+        antialias::wu_line_points(x1, y1, x2, y2, f);
+    } else {
+        line_points(x1, y1, x2, y2, |x, y| f(x, y, 1.0));
+    }
+}
 
-        let (w, h) = self.canvas.size();
+/// Calls `f` for every pixel of a Bresenham line, including both end points.
+fn line_points(mut x1: i32, mut y1: i32, x2: i32, y2: i32, mut f: impl FnMut(i32, i32)) {
+    let dx = (x2 - x1).abs();
+    let dy = (y2 - y1).abs();
 
-        for px in 0..w {
-            for py in 0..h {
-                let dx = px - x;
-                let dy = py - y;
+    let sx = if x1 < x2 { 1 } else { -1 };
+    let sy = if y1 < y2 { 1 } else { -1 };
 
-                let d2 = dx * dx + dy * dy;
+    let mut err = dx - dy;
 
-                if !is_transparent(&self.fill) && d2 < r2 {
-                    self.canvas.set(px, py, self.fill);
-                }
+    loop {
+        f(x1, y1);
 
-                if !is_transparent(&self.outline) {
-                    let diff = (d2 - r2).abs();
-                    // We're comparing squares so abs difference of 25 is 5 pixels
-                    if diff <= 25 {
-                        self.canvas.set(px, py, self.outline);
-                    }
-                }
-            }
+        if x1 == x2 && y1 == y2 {
+            break;
+        }
+
+        let e2 = 2 * err;
+
+        if e2 > -dy {
+            err -= dy;
+            x1 += sx;
+        }
+
+        if e2 < dx {
+            err += dx;
+            y1 += sy;
         }
     }
 }
 
-fn is_transparent(color: &Color) -> bool {
-    color.a() == 0.0
+fn is_transparent(paint: &Paint) -> bool {
+    paint.is_transparent()
+}
+
+/// Which of the shape's paints to draw with.
+#[derive(Clone, Copy)]
+enum Layer {
+    Fill,
+    Outline,
 }
@@ -0,0 +1,291 @@
+use super::polygon::{crossings_at, Edge};
+use super::{is_transparent, FillRule, Layer, Shape};
+use crate::{Canvas, Color, Rect};
+
+/// Rows sampled per pixel when computing the coverage of polygon edges.
+const SUBSAMPLES: i32 = 4;
+
+/// Coverage below this isn't visible in 8 bit channels.
+const MIN_COVERAGE: f32 = 1.0 / 512.0;
+
+impl<'a, C> Shape<'a, C>
+where
+    C: Canvas,
+{
+    /// Blends the pixels of row `y` starting at `x0`, each with its own coverage.
+    ///
+    /// Fully covered runs go through `blend_span`, the rest have their alpha scaled by the coverage.
+    pub(super) fn blend_coverage_row(&mut self, y: i32, x0: i32, coverage: &[f32], layer: Layer) {
+        let mut x = 0;
+
+        while x < coverage.len() {
+            if coverage[x] >= 1.0 - MIN_COVERAGE {
+                let start = x;
+
+                while x < coverage.len() && coverage[x] >= 1.0 - MIN_COVERAGE {
+                    x += 1;
+                }
+
+                self.paint_span(layer, y, x0 + start as i32, x0 + x as i32 - 1);
+            } else {
+                self.blend_coverage(x0 + x as i32, y, layer, coverage[x]);
+                x += 1;
+            }
+        }
+    }
+
+    /// Blends a single pixel with its alpha scaled by `coverage`.
+    pub(super) fn blend_coverage(&mut self, x: i32, y: i32, layer: Layer, coverage: f32) {
+        if coverage < MIN_COVERAGE {
+            return;
+        }
+
+        let color = self.color_at(layer, x, y);
+
+        self.canvas
+            .blend(x, y, with_coverage(color, coverage), self.blend);
+    }
+
+    /// Antialiased version of `fill_contours`.
+    pub(super) fn fill_contours_antialiased(
+        &mut self,
+        contours: &[Vec<(f32, f32)>],
+        rule: FillRule,
+        layer: Layer,
+    ) {
+        let clip = self.canvas.clip_rect();
+
+        for_each_coverage_row(contours, rule, clip, |y, x0, coverage| {
+            self.blend_coverage_row(y, x0, coverage, layer);
+        });
+    }
+
+    /// Antialiased ellipse with the given radii, measured from the center of the
+    /// center pixel to the outside of the edge pixels.
+    pub(super) fn ellipse_antialiased(&mut self, cx: i32, cy: i32, rx: f32, ry: f32) {
+        let draw_fill = !is_transparent(&self.fill);
+        let draw_outline = !is_transparent(&self.outline);
+        let dashed = self.dash_pattern().is_some();
+
+        let width = self.stroke_width.max(1) as f32;
+        let (inner_rx, inner_ry) = (rx - width, ry - width);
+
+        let half_w = rx.ceil() as i32;
+        let half_h = ry.ceil() as i32;
+
+        let (rows, columns) = self.clipped_offsets(cx, cy, half_w, half_h);
+        let x0 = cx + *columns.start();
+
+        let mut fill = Vec::with_capacity(2 * half_w as usize + 1);
+        let mut outline = Vec::with_capacity(2 * half_w as usize + 1);
+
+        for dy in rows {
+            fill.clear();
+            outline.clear();
+
+            for dx in columns.clone() {
+                let outer = ellipse_coverage(dx as f32, dy as f32, rx, ry);
+
+                if draw_outline {
+                    let inner = ellipse_coverage(dx as f32, dy as f32, inner_rx, inner_ry);
+
+                    fill.push(inner);
+                    outline.push(outer - inner);
+                } else {
+                    fill.push(outer);
+                }
+            }
+
+            if draw_fill {
+                self.blend_coverage_row(cy + dy, x0, &fill, Layer::Fill);
+            }
+
+            // Dashed outlines are stroked separately
+            if draw_outline && !dashed {
+                self.blend_coverage_row(cy + dy, x0, &outline, Layer::Outline);
+            }
+        }
+    }
+}
+
+/// Calls `f(x, y, coverage)` for the pixels of a Xiaolin Wu line between two pixel centers.
+pub(super) fn wu_line_points(x1: i32, y1: i32, x2: i32, y2: i32, mut f: impl FnMut(i32, i32, f32)) {
+    let steep = (y2 - y1).abs() > (x2 - x1).abs();
+
+    // Walk along the major axis, calling it x
+    let (a, b) = if steep {
+        ((y1, x1), (y2, x2))
+    } else {
+        ((x1, y1), (x2, y2))
+    };
+
+    let mut plot = |major: i32, minor: i32, coverage: f32| {
+        if coverage >= MIN_COVERAGE {
+            if steep {
+                f(minor, major, coverage);
+            } else {
+                f(major, minor, coverage);
+            }
+        }
+    };
+
+    if a.0 == b.0 {
+        plot(a.0, a.1, 1.0);
+        return;
+    }
+
+    let gradient = (b.1 - a.1) as f32 / (b.0 - a.0) as f32;
+    let step = if a.0 < b.0 { 1 } else { -1 };
+
+    let mut major = a.0;
+
+    loop {
+        let minor = a.1 as f32 + (major - a.0) as f32 * gradient;
+        let base = minor.floor();
+        let frac = minor - base;
+
+        plot(major, base as i32, 1.0 - frac);
+        plot(major, base as i32 + 1, frac);
+
+        if major == b.0 {
+            break;
+        }
+
+        major += step;
+    }
+}
+
+/// Scanline polygon fill that computes how much of each pixel is covered.
+///
+/// Each pixel row is sampled at `SUBSAMPLES` heights with exact horizontal coverage,
+/// and `f(y, x0, coverage)` is called with the coverage of the row's pixels starting at `x0`.
+fn for_each_coverage_row(
+    contours: &[Vec<(f32, f32)>],
+    rule: FillRule,
+    clip: Rect,
+    mut f: impl FnMut(i32, i32, &[f32]),
+) {
+    let edges = Edge::from_contours(contours);
+
+    if edges.is_empty() || clip.is_empty() {
+        return;
+    }
+
+    let min_y = edges.iter().map(|e| e.y0).fold(f32::INFINITY, f32::min);
+    let max_y = edges.iter().map(|e| e.y1).fold(f32::NEG_INFINITY, f32::max);
+    let min_x = edges
+        .iter()
+        .map(|e| e.min_x())
+        .fold(f32::INFINITY, f32::min);
+    let max_x = edges
+        .iter()
+        .map(|e| e.max_x())
+        .fold(f32::NEG_INFINITY, f32::max);
+
+    let first_row = ((min_y + 0.5).floor() as i32).max(clip.y);
+    let last_row = ((max_y + 0.5).ceil() as i32 - 1).min(clip.y + clip.h - 1);
+    let first_col = ((min_x + 0.5).floor() as i32).max(clip.x);
+    let last_col = ((max_x + 0.5).ceil() as i32 - 1).min(clip.x + clip.w - 1);
+
+    if first_col > last_col {
+        return;
+    }
+
+    let left = first_col as f32 - 0.5;
+    let right = last_col as f32 + 0.5;
+    let width = (last_col - first_col + 1) as usize;
+
+    // Partial coverage at the ends of each run, and the changes in full coverage in between
+    let mut partial = vec![0.0; width];
+    let mut delta = vec![0.0; width + 1];
+    let mut coverage = vec![0.0; width];
+    let mut crossings = Vec::new();
+
+    let weight = 1.0 / SUBSAMPLES as f32;
+
+    for y in first_row..=last_row {
+        partial.fill(0.0);
+        delta.fill(0.0);
+
+        for sample in 0..SUBSAMPLES {
+            let sample_y = y as f32 - 0.5 + (sample as f32 + 0.5) * weight;
+
+            crossings_at(&edges, sample_y, &mut crossings);
+
+            let mut winding = 0;
+
+            for pair in crossings.windows(2) {
+                winding += pair[0].1;
+
+                let inside = match rule {
+                    FillRule::NonZero => winding != 0,
+                    FillRule::EvenOdd => winding % 2 != 0,
+                };
+
+                let a = pair[0].0.clamp(left, right) - left;
+                let b = pair[1].0.clamp(left, right) - left;
+
+                if !inside || a >= b {
+                    continue;
+                }
+
+                let first = (a as usize).min(width - 1);
+                let last = (b as usize).min(width - 1);
+
+                if first == last {
+                    partial[first] += (b - a) * weight;
+                } else {
+                    partial[first] += (first as f32 + 1.0 - a) * weight;
+                    partial[last] += (b - last as f32) * weight;
+                    delta[first + 1] += weight;
+                    delta[last] -= weight;
+                }
+            }
+        }
+
+        let mut full = 0.0;
+
+        for x in 0..width {
+            full += delta[x];
+            coverage[x] = (full + partial[x]).min(1.0);
+        }
+
+        // Leave out the empty ends of the row
+        let Some(start) = coverage.iter().position(|&c| c >= MIN_COVERAGE) else {
+            continue;
+        };
+        let end = coverage
+            .iter()
+            .rposition(|&c| c >= MIN_COVERAGE)
+            .unwrap_or(start);
+
+        f(y, first_col + start as i32, &coverage[start..=end]);
+    }
+}
+
+/// How much of the pixel at `(dx, dy)` from the center lies inside the ellipse,
+/// estimated from the distance of its center to the edge.
+fn ellipse_coverage(dx: f32, dy: f32, rx: f32, ry: f32) -> f32 {
+    if rx <= 0.0 || ry <= 0.0 {
+        return 0.0;
+    }
+
+    let (nx, ny) = (dx / rx, dy / ry);
+    let f = (nx * nx + ny * ny).sqrt();
+
+    if f == 0.0 {
+        return (rx.min(ry) + 0.5).min(1.0);
+    }
+
+    // First order approximation of the signed distance, which is exact for circles
+    let gradient = ((nx / rx).powi(2) + (ny / ry).powi(2)).sqrt() / f;
+    let distance = (f - 1.0) / gradient;
+
+    (0.5 - distance).clamp(0.0, 1.0)
+}
+
+fn with_coverage(color: Color, coverage: f32) -> Color {
+    let mut color = color;
+    color.set_a(color.a() * coverage.min(1.0));
+    color
+}
@@ -0,0 +1,145 @@
+use super::Shape;
+use crate::Canvas;
+
+type Point = (f32, f32);
+
+/// How far flattened curves may stray from the real ones, in pixels.
+const TOLERANCE: f32 = 0.25;
+
+/// Stops subdividing after this many halvings, even for degenerate curves.
+const MAX_DEPTH: u32 = 16;
+
+impl<'a, C> Shape<'a, C>
+where
+    C: Canvas,
+{
+    /// Draws a quadratic Bézier curve from `p0` to `p2` with the outline color, bending towards `p1`.
+    pub fn quad_bezier(&mut self, p0: (i32, i32), p1: (i32, i32), p2: (i32, i32)) {
+        let [p0, p1, p2] = [p0, p1, p2].map(to_f32);
+
+        let mut points = vec![p0];
+        flatten_quad(p0, p1, p2, &mut points);
+
+        self.curve(&points);
+    }
+
+    /// Draws a cubic Bézier curve from `p0` to `p3` with the outline color,
+    /// leaving towards `p1` and arriving from `p2`.
+    pub fn cubic_bezier(&mut self, p0: (i32, i32), p1: (i32, i32), p2: (i32, i32), p3: (i32, i32)) {
+        let [p0, p1, p2, p3] = [p0, p1, p2, p3].map(to_f32);
+
+        let mut points = vec![p0];
+        flatten_cubic(p0, p1, p2, p3, &mut points);
+
+        self.curve(&points);
+    }
+
+    /// Draws a flattened curve, keeping its precision if it's wider than a pixel.
+    fn curve(&mut self, points: &[Point]) {
+        self.set_origin(
+            points
+                .iter()
+                .map(|&(x, y)| (x.floor() as i32, y.floor() as i32)),
+        );
+
+        if self.stroke_width > 1 {
+            self.stroke_path(points, false);
+            return;
+        }
+
+        let mut pixels: Vec<_> = points
+            .iter()
+            .map(|&(x, y)| (x.round() as i32, y.round() as i32))
+            .collect();
+        pixels.dedup();
+
+        self.polyline(&pixels);
+    }
+}
+
+/// Appends points along a quadratic Bézier curve to `out`, leaving out `p0`.
+///
+/// Straighter parts of the curve get fewer points.
+pub(super) fn flatten_quad(p0: Point, p1: Point, p2: Point, out: &mut Vec<Point>) {
+    subdivide_quad(p0, p1, p2, MAX_DEPTH, out);
+}
+
+/// Appends points along a cubic Bézier curve to `out`, leaving out `p0`.
+///
+/// Straighter parts of the curve get fewer points.
+pub(super) fn flatten_cubic(p0: Point, p1: Point, p2: Point, p3: Point, out: &mut Vec<Point>) {
+    subdivide_cubic(p0, p1, p2, p3, MAX_DEPTH, out);
+}
+
+/// Appends points along a circular arc to `out`, leaving out its start.
+///
+/// Angles are in radians, and the arc goes clockwise for positive `sweep`.
+pub(super) fn flatten_arc(
+    center: Point,
+    radius: f32,
+    start: f32,
+    sweep: f32,
+    out: &mut Vec<Point>,
+) {
+    // Largest step that keeps the segments within the tolerance of the arc
+    let step = 2.0 * (1.0 - TOLERANCE / radius.max(TOLERANCE)).acos();
+    let segments = ((sweep.abs() / step.max(0.01)).ceil() as usize).clamp(1, 1024);
+
+    for i in 1..=segments {
+        let angle = start + sweep * i as f32 / segments as f32;
+        out.push((
+            center.0 + radius * angle.cos(),
+            center.1 + radius * angle.sin(),
+        ));
+    }
+}
+
+fn subdivide_quad(p0: Point, p1: Point, p2: Point, depth: u32, out: &mut Vec<Point>) {
+    // The curve strays at most a quarter of this from its chord
+    let (dx, dy) = (p0.0 - 2.0 * p1.0 + p2.0, p0.1 - 2.0 * p1.1 + p2.1);
+
+    if depth == 0 || dx * dx + dy * dy <= 16.0 * TOLERANCE * TOLERANCE {
+        out.push(p2);
+        return;
+    }
+
+    let a = mid(p0, p1);
+    let b = mid(p1, p2);
+    let m = mid(a, b);
+
+    subdivide_quad(p0, a, m, depth - 1, out);
+    subdivide_quad(m, b, p2, depth - 1, out);
+}
+
+fn subdivide_cubic(p0: Point, p1: Point, p2: Point, p3: Point, depth: u32, out: &mut Vec<Point>) {
+    // Bounds how far the curve strays from its chord, times four
+    let ux = 3.0 * p1.0 - 2.0 * p0.0 - p3.0;
+    let uy = 3.0 * p1.1 - 2.0 * p0.1 - p3.1;
+    let vx = 3.0 * p2.0 - p0.0 - 2.0 * p3.0;
+    let vy = 3.0 * p2.1 - p0.1 - 2.0 * p3.1;
+
+    let flatness = (ux * ux).max(vx * vx) + (uy * uy).max(vy * vy);
+
+    if depth == 0 || flatness <= 16.0 * TOLERANCE * TOLERANCE {
+        out.push(p3);
+        return;
+    }
+
+    let a = mid(p0, p1);
+    let b = mid(p1, p2);
+    let c = mid(p2, p3);
+    let d = mid(a, b);
+    let e = mid(b, c);
+    let m = mid(d, e);
+
+    subdivide_cubic(p0, a, d, m, depth - 1, out);
+    subdivide_cubic(m, e, c, p3, depth - 1, out);
+}
+
+fn mid(a: Point, b: Point) -> Point {
+    ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0)
+}
+
+fn to_f32((x, y): (i32, i32)) -> Point {
+    (x as f32, y as f32)
+}
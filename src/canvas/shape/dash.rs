@@ -0,0 +1,108 @@
+type Point = (f32, f32);
+
+/// A repeating pattern of alternating on and off lengths along a path.
+pub(super) struct Dash {
+    pattern: Vec<f32>,
+    total: f32,
+    offset: f32,
+}
+
+impl Dash {
+    /// Returns `None` for an empty or invalid pattern, meaning the path is drawn solid.
+    pub(super) fn new(pattern: &[i32], offset: i32) -> Option<Self> {
+        if pattern.is_empty() || pattern.iter().any(|&len| len < 0) {
+            return None;
+        }
+
+        let mut pattern: Vec<f32> = pattern.iter().map(|&len| len as f32).collect();
+
+        // An odd number of lengths alternates between on and off each time it repeats
+        if pattern.len() % 2 != 0 {
+            pattern.extend_from_slice(&pattern.clone());
+        }
+
+        let total: f32 = pattern.iter().sum();
+
+        if total <= 0.0 {
+            return None;
+        }
+
+        Some(Self {
+            pattern,
+            total,
+            offset: offset as f32,
+        })
+    }
+
+    /// Whether the point `distance` along the path is drawn.
+    pub(super) fn is_on(&self, distance: f32) -> bool {
+        self.locate(distance).0 % 2 == 0
+    }
+
+    /// Splits a path into the pieces that are drawn.
+    pub(super) fn split(&self, points: &[Point], closed: bool) -> Vec<Vec<Point>> {
+        let mut dashes = Vec::new();
+
+        let Some(&first) = points.first() else {
+            return dashes;
+        };
+
+        let (mut index, mut left) = self.locate(0.0);
+        let mut current = if index % 2 == 0 {
+            vec![first]
+        } else {
+            Vec::new()
+        };
+
+        let closing = closed.then(|| (points[points.len() - 1], first));
+        let segments = points.windows(2).map(|pair| (pair[0], pair[1]));
+
+        for (p, q) in segments.chain(closing) {
+            let len = ((q.0 - p.0).powi(2) + (q.1 - p.1).powi(2)).sqrt();
+            let mut t = 0.0;
+
+            while len - t > left {
+                t += left;
+
+                let point = (p.0 + (q.0 - p.0) * t / len, p.1 + (q.1 - p.1) * t / len);
+
+                if index % 2 == 0 {
+                    current.push(point);
+                    dashes.push(std::mem::take(&mut current));
+                } else {
+                    current = vec![point];
+                }
+
+                index = (index + 1) % self.pattern.len();
+                left = self.pattern[index];
+            }
+
+            left -= len - t;
+
+            if index % 2 == 0 {
+                current.push(q);
+            }
+        }
+
+        if index % 2 == 0 && !current.is_empty() {
+            dashes.push(current);
+        }
+
+        dashes
+    }
+
+    /// Finds the pattern entry at `distance` and how much of it is left.
+    fn locate(&self, distance: f32) -> (usize, f32) {
+        let mut position = (distance + self.offset).rem_euclid(self.total);
+
+        for (index, &len) in self.pattern.iter().enumerate() {
+            if position < len {
+                return (index, len - position);
+            }
+
+            position -= len;
+        }
+
+        (0, self.pattern[0])
+    }
+}
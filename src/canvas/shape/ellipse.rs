@@ -0,0 +1,478 @@
+use std::f32::consts::TAU;
+use std::ops::RangeInclusive;
+
+use super::{is_transparent, FillRule, Layer, Shape};
+use crate::Canvas;
+
+impl<'a, C> Shape<'a, C>
+where
+    C: Canvas,
+{
+    pub fn circle(&mut self, x: i32, y: i32, r: i32) {
+        if r < 0 {
+            return;
+        }
+
+        self.set_origin([(x - r, y - r)]);
+
+        let outer = r as f32 + 0.5;
+
+        if self.antialias {
+            self.ellipse_antialiased(x, y, outer, outer);
+            self.dashed_outline(x, y, outer, outer, &[]);
+            return;
+        }
+
+        let extents = circle_extents(r);
+        let inner = (self.stroke_width > 1).then(|| circle_extents(r - self.stroke_width));
+
+        self.symmetric(x, y, &extents, inner.as_deref());
+        self.dashed_outline(x, y, outer, outer, &extents);
+    }
+
+    pub fn ellipse(&mut self, cx: i32, cy: i32, rx: i32, ry: i32) {
+        if rx < 0 || ry < 0 {
+            return;
+        }
+
+        self.set_origin([(cx - rx, cy - ry)]);
+
+        let (outer_rx, outer_ry) = (rx as f32 + 0.5, ry as f32 + 0.5);
+
+        if self.antialias {
+            self.ellipse_antialiased(cx, cy, outer_rx, outer_ry);
+            self.dashed_outline(cx, cy, outer_rx, outer_ry, &[]);
+            return;
+        }
+
+        let extents = ellipse_extents(rx, ry);
+        let inner = (self.stroke_width > 1)
+            .then(|| ellipse_extents(rx - self.stroke_width, ry - self.stroke_width));
+
+        self.symmetric(cx, cy, &extents, inner.as_deref());
+        self.dashed_outline(cx, cy, outer_rx, outer_ry, &extents);
+    }
+
+    /// Draws the part of the ellipse outline between two angles, using the outline color.
+    ///
+    /// Angles are in radians, starting at the positive x axis and going clockwise
+    /// (since y points down), from `start` to `end`.
+    pub fn arc(&mut self, cx: i32, cy: i32, rx: i32, ry: i32, start: f32, end: f32) {
+        if rx < 0 || ry < 0 || is_transparent(&self.outline) {
+            return;
+        }
+
+        self.set_origin([(cx - rx, cy - ry)]);
+
+        let sweep = Sweep::new(start, end);
+
+        // Wide outlines are stroked along the ellipse, aliased or not
+        if self.antialias || self.stroke_width > 1 {
+            let width = self.stroke_width.max(1) as f32;
+            let inset = 0.5 - width / 2.0;
+            let points = sweep.contour(cx, cy, rx as f32 + inset, ry as f32 + inset);
+
+            self.stroke_path(&points, sweep.is_full());
+            return;
+        }
+
+        // Dashes are counted from the start of the arc, so they need the pixels outside the clip too
+        let (rows, columns) = if self.dash_pattern().is_some() {
+            (-ry..=ry, -rx..=rx)
+        } else {
+            self.clipped_offsets(cx, cy, rx, ry)
+        };
+
+        let extents = ellipse_extents(rx, ry);
+        let mut pixels = Vec::new();
+
+        edge_pixels(&extents, rows, columns, |dx, dy| {
+            if sweep.contains(dx, dy) {
+                pixels.push((dx, dy));
+            }
+        });
+
+        self.blend_outline_pixels(cx, cy, pixels, sweep.start);
+    }
+
+    /// Draws a pie slice of the ellipse between two angles, outlined along both the arc and the radii.
+    ///
+    /// Angles work the same as in `arc`.
+    pub fn pie(&mut self, cx: i32, cy: i32, rx: i32, ry: i32, start: f32, end: f32) {
+        if rx < 0 || ry < 0 {
+            return;
+        }
+
+        self.set_origin([(cx - rx, cy - ry)]);
+
+        let sweep = Sweep::new(start, end);
+
+        if sweep.is_full() {
+            self.ellipse(cx, cy, rx, ry);
+            return;
+        }
+
+        let draw_fill = !is_transparent(&self.fill);
+        let draw_outline = !is_transparent(&self.outline);
+
+        if self.antialias || self.stroke_width > 1 {
+            // The outline is centered inside the edge, like the single pixel one
+            let width = self.stroke_width.max(1) as f32;
+            let inset = if draw_outline { 0.5 - width / 2.0 } else { 0.5 };
+
+            let mut points = sweep.contour(cx, cy, rx as f32 + inset, ry as f32 + inset);
+            points.insert(0, (cx as f32, cy as f32));
+
+            if draw_fill {
+                self.fill_contours(&[points.clone()], FillRule::NonZero, Layer::Fill);
+            }
+
+            if draw_outline {
+                self.stroke_path(&points, true);
+            }
+
+            return;
+        }
+
+        let extents = ellipse_extents(rx, ry);
+        let in_pie = |dx: i32, dy: i32| in_ellipse(&extents, dx, dy) && sweep.contains(dx, dy);
+
+        let (rows, columns) = self.clipped_offsets(cx, cy, rx, ry);
+
+        for dy in rows {
+            for dx in columns.clone() {
+                if !in_pie(dx, dy) {
+                    continue;
+                }
+
+                if draw_outline && is_edge(dx, dy, in_pie) {
+                    self.paint_pixel(Layer::Outline, cx + dx, cy + dy);
+                } else if draw_fill {
+                    self.paint_pixel(Layer::Fill, cx + dx, cy + dy);
+                }
+            }
+        }
+    }
+
+    /// Draws the outline of an ellipse if it's dashed, since the dashes need to follow it around.
+    ///
+    /// The radii reach the outside of the edge pixels. Thin aliased outlines walk along
+    /// the edge pixels of `extents` to look the same as solid ones.
+    fn dashed_outline(&mut self, cx: i32, cy: i32, rx: f32, ry: f32, extents: &[i32]) {
+        if is_transparent(&self.outline) || self.dash_pattern().is_none() {
+            return;
+        }
+
+        if self.antialias || self.stroke_width > 1 {
+            let inset = self.stroke_width.max(1) as f32 / 2.0;
+            let points = Sweep::new(0.0, TAU).contour(cx, cy, rx - inset, ry - inset);
+
+            self.stroke_path(&points, true);
+            return;
+        }
+
+        let half_h = extents.len() as i32 - 1;
+        let half_w = extents.iter().copied().max().unwrap_or(-1);
+
+        let mut pixels = Vec::new();
+        edge_pixels(extents, -half_h..=half_h, -half_w..=half_w, |dx, dy| {
+            pixels.push((dx, dy));
+        });
+
+        self.blend_outline_pixels(cx, cy, pixels, 0.0);
+    }
+
+    /// The offsets from `(cx, cy)` up to `half_w` and `half_h` away that are inside the clip,
+    /// as ranges of rows and columns.
+    pub(super) fn clipped_offsets(
+        &self,
+        cx: i32,
+        cy: i32,
+        half_w: i32,
+        half_h: i32,
+    ) -> (RangeInclusive<i32>, RangeInclusive<i32>) {
+        let clip = self.canvas.clip_rect();
+
+        let rows = (-half_h).max(clip.y - cy)..=half_h.min(clip.y + clip.h - 1 - cy);
+        let columns = (-half_w).max(clip.x - cx)..=half_w.min(clip.x + clip.w - 1 - cx);
+
+        (rows, columns)
+    }
+
+    /// Blends outline pixels around `(cx, cy)`, applying the dash pattern in clockwise order
+    /// starting at the angle `start`.
+    fn blend_outline_pixels(&mut self, cx: i32, cy: i32, mut pixels: Vec<(i32, i32)>, start: f32) {
+        let dash = self.dash_pattern();
+
+        if dash.is_some() {
+            let angle = |&(dx, dy): &(i32, i32)| (dy as f32).atan2(dx as f32) - start;
+            pixels.sort_by(|a, b| {
+                angle(a)
+                    .rem_euclid(TAU)
+                    .total_cmp(&angle(b).rem_euclid(TAU))
+            });
+        }
+
+        for (i, (dx, dy)) in pixels.into_iter().enumerate() {
+            if dash.as_ref().map_or(true, |dash| dash.is_on(i as f32)) {
+                self.paint_pixel(Layer::Outline, cx + dx, cy + dy);
+            }
+        }
+    }
+
+    /// Draws a shape that's symmetric around `(cx, cy)`, where `extents[dy]` is
+    /// the half width of the rows `dy` above and below the center.
+    ///
+    /// The outline is everything outside of the `inner` extents. Without those it's made of
+    /// the pixels with a horizontal or vertical neighbour outside the shape,
+    /// so it's exactly one pixel thick everywhere.
+    fn symmetric(&mut self, cx: i32, cy: i32, extents: &[i32], inner: Option<&[i32]>) {
+        let draw_fill = !is_transparent(&self.fill);
+        let draw_outline = !is_transparent(&self.outline);
+        let dashed = self.dash_pattern().is_some();
+
+        let last = extents.len() as i32 - 1;
+
+        for dy in -last..=last {
+            let row = dy.unsigned_abs() as usize;
+            let y = cy + dy;
+
+            let outer = extents[row];
+            let next = extents.get(row + 1).copied().unwrap_or(-1);
+
+            if !draw_outline {
+                if draw_fill {
+                    self.paint_span(Layer::Fill, y, cx - outer, cx + outer);
+                }
+
+                continue;
+            }
+
+            let inner = match inner {
+                Some(inner) => inner.get(row).copied().unwrap_or(-1),
+                None => (outer - 1).min(next),
+            };
+
+            if draw_fill && inner >= 0 {
+                self.paint_span(Layer::Fill, y, cx - inner, cx + inner);
+            }
+
+            // Dashed outlines are drawn by `dashed_outline`
+            if dashed {
+                continue;
+            }
+
+            if inner < 0 {
+                self.paint_span(Layer::Outline, y, cx - outer, cx + outer);
+                continue;
+            }
+
+            self.paint_span(Layer::Outline, y, cx - outer, cx - inner - 1);
+            self.paint_span(Layer::Outline, y, cx + inner + 1, cx + outer);
+        }
+    }
+}
+
+/// Half widths of a midpoint circle's rows, indexed by the distance from the center row.
+pub(super) fn circle_extents(r: i32) -> Vec<i32> {
+    if r < 0 {
+        return Vec::new();
+    }
+
+    let mut extents = vec![-1; r as usize + 1];
+
+    let mut x = r;
+    let mut y = 0;
+    let mut err = 1 - r;
+
+    while x >= y {
+        extents[y as usize] = extents[y as usize].max(x);
+        extents[x as usize] = extents[x as usize].max(y);
+
+        y += 1;
+
+        if err < 0 {
+            err += 2 * y + 1;
+        } else {
+            x -= 1;
+            err += 2 * (y - x) + 1;
+        }
+    }
+
+    extents
+}
+
+/// Half widths of a midpoint ellipse's rows, indexed by the distance from the center row.
+fn ellipse_extents(rx: i32, ry: i32) -> Vec<i32> {
+    if rx < 0 || ry < 0 {
+        return Vec::new();
+    }
+
+    if ry == 0 {
+        return vec![rx];
+    }
+
+    let mut extents = vec![-1; ry as usize + 1];
+
+    // The decision variables grow with the fourth power of the radii, which overflows i64
+    // long before the extents stop fitting in memory
+    let rx2 = rx as i128 * rx as i128;
+    let ry2 = ry as i128 * ry as i128;
+
+    let mut x: i128 = 0;
+    let mut y: i128 = ry as i128;
+
+    // Decision variables are scaled by 4 to stay in integers
+    let mut px: i128 = 0;
+    let mut py: i128 = 2 * rx2 * y;
+    let mut p = 4 * ry2 - 4 * rx2 * y + rx2;
+
+    while px < py {
+        extents[y as usize] = extents[y as usize].max(x as i32);
+
+        x += 1;
+        px += 2 * ry2;
+
+        if p < 0 {
+            p += 4 * (ry2 + px);
+        } else {
+            y -= 1;
+            py -= 2 * rx2;
+            p += 4 * (ry2 + px - py);
+        }
+    }
+
+    p = ry2 * (2 * x + 1) * (2 * x + 1) + 4 * rx2 * (y - 1) * (y - 1) - 4 * rx2 * ry2;
+
+    while y >= 0 {
+        extents[y as usize] = extents[y as usize].max(x as i32);
+
+        y -= 1;
+        py -= 2 * rx2;
+
+        if p > 0 {
+            p += 4 * (rx2 - py);
+        } else {
+            x += 1;
+            px += 2 * ry2;
+            p += 4 * (rx2 - py + px);
+        }
+    }
+
+    extents
+}
+
+fn in_ellipse(extents: &[i32], dx: i32, dy: i32) -> bool {
+    extents
+        .get(dy.unsigned_abs() as usize)
+        .is_some_and(|&half_width| dx.abs() <= half_width)
+}
+
+/// Calls `f(dx, dy)` for the pixels within `rows` and `columns` of a symmetric shape that
+/// have a horizontal or vertical neighbour outside of it, going row by row.
+fn edge_pixels(
+    extents: &[i32],
+    rows: RangeInclusive<i32>,
+    columns: RangeInclusive<i32>,
+    mut f: impl FnMut(i32, i32),
+) {
+    let extent = |dy: i32| {
+        extents
+            .get(dy.unsigned_abs() as usize)
+            .copied()
+            .unwrap_or(-1)
+    };
+
+    for dy in rows {
+        let outer = extent(dy);
+
+        if outer < 0 {
+            continue;
+        }
+
+        // Pixels wider than the rows above or below have an outside neighbour there
+        let inner = (outer - 1).min(extent(dy - 1)).min(extent(dy + 1));
+
+        let runs = if inner < 0 {
+            [(-outer, outer), (1, 0)]
+        } else {
+            [(-outer, -inner - 1), (inner + 1, outer)]
+        };
+
+        for (start, end) in runs {
+            for dx in start.max(*columns.start())..=end.min(*columns.end()) {
+                f(dx, dy);
+            }
+        }
+    }
+}
+
+/// Whether a pixel has a horizontal or vertical neighbour outside the shape.
+fn is_edge(dx: i32, dy: i32, contains: impl Fn(i32, i32) -> bool) -> bool {
+    !contains(dx - 1, dy) || !contains(dx + 1, dy) || !contains(dx, dy - 1) || !contains(dx, dy + 1)
+}
+
+/// A range of angles, going clockwise from `start`.
+struct Sweep {
+    start: f32,
+    len: f32,
+}
+
+impl Sweep {
+    fn new(start: f32, end: f32) -> Self {
+        let len = if end - start >= TAU {
+            TAU
+        } else {
+            (end - start).rem_euclid(TAU)
+        };
+
+        Self {
+            start: start.rem_euclid(TAU),
+            len,
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.len >= TAU
+    }
+
+    fn contains(&self, dx: i32, dy: i32) -> bool {
+        if dx == 0 && dy == 0 {
+            return true;
+        }
+
+        let angle = (dy as f32).atan2(dx as f32);
+
+        (angle - self.start).rem_euclid(TAU) <= self.len
+    }
+
+    /// Points along the part of an ellipse covered by the sweep.
+    fn contour(&self, cx: i32, cy: i32, rx: f32, ry: f32) -> Vec<(f32, f32)> {
+        let rx = rx.max(0.0);
+        let ry = ry.max(0.0);
+
+        let segments = ((rx.max(ry) * self.len).ceil() as usize).clamp(2, 256);
+
+        // Full ellipses are closed, so they don't repeat the first point
+        let count = if self.is_full() {
+            segments
+        } else {
+            segments + 1
+        };
+
+        (0..count)
+            .map(|i| {
+                let angle = self.start + self.len * i as f32 / segments as f32;
+                let (sin, cos) = angle.sin_cos();
+
+                // Distance to the edge along the angle, so the contour matches `contains`
+                let r = rx * ry
+                    / ((ry * cos).powi(2) + (rx * sin).powi(2))
+                        .sqrt()
+                        .max(f32::EPSILON);
+
+                (cx as f32 + r * cos, cy as f32 + r * sin)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,202 @@
+use std::f32::consts::PI;
+
+use super::curve::{flatten_arc, flatten_cubic, flatten_quad};
+use super::{is_transparent, FillRule, Layer, Shape};
+use crate::Canvas;
+
+type Point = (f32, f32);
+
+/// An outline made of lines and curves, drawn with `Shape::path`.
+///
+/// Coordinates are in pixels, with pixel centers at whole numbers. Curves are flattened
+/// into lines as they're added.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Path {
+    subpaths: Vec<SubPath>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct SubPath {
+    points: Vec<Point>,
+    closed: bool,
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a new subpath at `(x, y)`.
+    pub fn move_to(&mut self, x: f32, y: f32) -> &mut Self {
+        // A subpath without any segments doesn't draw anything
+        if let Some(last) = self.subpaths.last() {
+            if last.points.len() == 1 && !last.closed {
+                self.subpaths.pop();
+            }
+        }
+
+        self.subpaths.push(SubPath {
+            points: vec![(x, y)],
+            closed: false,
+        });
+
+        self
+    }
+
+    pub fn line_to(&mut self, x: f32, y: f32) -> &mut Self {
+        self.current().points.push((x, y));
+        self
+    }
+
+    /// Adds a quadratic Bézier curve to `(x, y)`, bending towards `(cx, cy)`.
+    pub fn quad_to(&mut self, cx: f32, cy: f32, x: f32, y: f32) -> &mut Self {
+        let points = &mut self.current().points;
+        let start = points[points.len() - 1];
+
+        flatten_quad(start, (cx, cy), (x, y), points);
+        self
+    }
+
+    /// Adds a cubic Bézier curve to `(x, y)`, leaving towards `(c1x, c1y)` and arriving from `(c2x, c2y)`.
+    pub fn cubic_to(
+        &mut self,
+        c1x: f32,
+        c1y: f32,
+        c2x: f32,
+        c2y: f32,
+        x: f32,
+        y: f32,
+    ) -> &mut Self {
+        let points = &mut self.current().points;
+        let start = points[points.len() - 1];
+
+        flatten_cubic(start, (c1x, c1y), (c2x, c2y), (x, y), points);
+        self
+    }
+
+    /// Adds a circular arc with the given radius that's tangent to the line from the current
+    /// point to `(x1, y1)` and the line from there to `(x2, y2)`, like the canvas API's `arcTo`.
+    ///
+    /// The path is connected to the start of the arc with a straight line and ends where the arc
+    /// touches the second line. Rounds the corners of a polygon when called for each of them.
+    pub fn arc_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, radius: f32) -> &mut Self {
+        let points = &mut self.current().points;
+        let p0 = points[points.len() - 1];
+
+        let d0 = normalize((p0.0 - x1, p0.1 - y1));
+        let d2 = normalize((x2 - x1, y2 - y1));
+
+        let (Some(d0), Some(d2)) = (d0, d2) else {
+            points.push((x1, y1));
+            return self;
+        };
+
+        let cos = (d0.0 * d2.0 + d0.1 * d2.1).clamp(-1.0, 1.0);
+        let half_angle = cos.acos() / 2.0;
+
+        // Straight lines have nothing to round off
+        if radius <= 0.0 || half_angle <= f32::EPSILON || half_angle >= PI / 2.0 - f32::EPSILON {
+            points.push((x1, y1));
+            return self;
+        }
+
+        let tangent = radius / half_angle.tan();
+        let start = (x1 + d0.0 * tangent, y1 + d0.1 * tangent);
+        let end = (x1 + d2.0 * tangent, y1 + d2.1 * tangent);
+
+        let Some(bisector) = normalize((d0.0 + d2.0, d0.1 + d2.1)) else {
+            points.push((x1, y1));
+            return self;
+        };
+
+        let distance = radius / half_angle.sin();
+        let center = (x1 + bisector.0 * distance, y1 + bisector.1 * distance);
+
+        let start_angle = (start.1 - center.1).atan2(start.0 - center.0);
+        let end_angle = (end.1 - center.1).atan2(end.0 - center.0);
+
+        // Take the short way around
+        let mut sweep = end_angle - start_angle;
+
+        if sweep > PI {
+            sweep -= 2.0 * PI;
+        } else if sweep < -PI {
+            sweep += 2.0 * PI;
+        }
+
+        points.push(start);
+        flatten_arc(center, radius, start_angle, sweep, points);
+
+        self
+    }
+
+    /// Closes the current subpath with a line back to its start.
+    pub fn close(&mut self) -> &mut Self {
+        if let Some(last) = self.subpaths.last_mut() {
+            last.closed = true;
+        }
+
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subpaths.is_empty()
+    }
+
+    /// The subpath that's being added to, starting a new one where the last ended if needed.
+    fn current(&mut self) -> &mut SubPath {
+        let start = match self.subpaths.last() {
+            Some(last) if !last.closed => None,
+            Some(last) => Some(last.points[0]),
+            None => Some((0.0, 0.0)),
+        };
+
+        if let Some(start) = start {
+            self.subpaths.push(SubPath {
+                points: vec![start],
+                closed: false,
+            });
+        }
+
+        self.subpaths.last_mut().unwrap()
+    }
+}
+
+impl<'a, C> Shape<'a, C>
+where
+    C: Canvas,
+{
+    /// Fills a path according to the fill rule, closing any open subpaths,
+    /// and strokes it with the outline color, width, caps, joins and dashes.
+    pub fn path(&mut self, path: &Path) {
+        let points = path.subpaths.iter().flat_map(|subpath| &subpath.points);
+        self.set_origin(points.map(|&(x, y)| (x.floor() as i32, y.floor() as i32)));
+
+        if !is_transparent(&self.fill) {
+            let contours: Vec<_> = path
+                .subpaths
+                .iter()
+                .map(|subpath| subpath.points.clone())
+                .collect();
+
+            self.fill_contours(&contours, self.fill_rule, Layer::Fill);
+        }
+
+        if !is_transparent(&self.outline) {
+            let contours: Vec<_> = path
+                .subpaths
+                .iter()
+                .flat_map(|subpath| self.outline_contours(&subpath.points, subpath.closed))
+                .collect();
+
+            // Subpaths overlapping each other still only get blended once
+            self.fill_contours(&contours, FillRule::NonZero, Layer::Outline);
+        }
+    }
+}
+
+fn normalize((x, y): Point) -> Option<Point> {
+    let len = (x * x + y * y).sqrt();
+
+    (len > f32::EPSILON).then(|| (x / len, y / len))
+}
@@ -0,0 +1,208 @@
+use super::{is_transparent, line_distance, line_pixels, Layer, Shape};
+use crate::{Canvas, Rect};
+
+/// Decides which parts of a self-intersecting or nested shape count as inside.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FillRule {
+    /// Inside if the outline winds around the point at least once in either direction.
+    #[default]
+    NonZero,
+    /// Inside if a ray from the point crosses the outline an odd number of times.
+    EvenOdd,
+}
+
+impl<'a, C> Shape<'a, C>
+where
+    C: Canvas,
+{
+    /// Draws a closed polygon through `points`, filled according to the fill rule.
+    ///
+    /// Like most rasterizers, a pixel is filled if its center lies inside the polygon,
+    /// with the right and bottom edges being exclusive so adjacent polygons don't overlap.
+    /// The outline is centered on the edges between the vertices.
+    pub fn polygon(&mut self, points: &[(i32, i32)]) {
+        self.set_origin(points.iter().copied());
+
+        if !is_transparent(&self.fill) && points.len() >= 3 {
+            let contour: Vec<_> = points.iter().map(|&(x, y)| (x as f32, y as f32)).collect();
+
+            self.fill_contours(&[contour], self.fill_rule, Layer::Fill);
+        }
+
+        if !is_transparent(&self.outline) {
+            self.closed_outline(points);
+        }
+    }
+
+    /// Fills the area inside the contours, honouring the clip.
+    pub(super) fn fill_contours(
+        &mut self,
+        contours: &[Vec<(f32, f32)>],
+        rule: FillRule,
+        layer: Layer,
+    ) {
+        if self.antialias {
+            self.fill_contours_antialiased(contours, rule, layer);
+            return;
+        }
+
+        let clip = self.canvas.clip_rect();
+
+        for_each_span(contours, rule, clip, |y, x0, x1| {
+            self.paint_span(layer, y, x0, x1);
+        });
+    }
+
+    /// Outlines a closed polygon without drawing any vertex twice.
+    pub(super) fn closed_outline(&mut self, points: &[(i32, i32)]) {
+        if self.stroke_width > 1 {
+            self.stroke(points, true);
+            return;
+        }
+
+        if let [(x, y)] = points {
+            self.paint_pixel(Layer::Outline, *x, *y);
+            return;
+        }
+
+        let dash = self.dash_pattern();
+        let mut start = 0;
+
+        for (i, &(x1, y1)) in points.iter().enumerate() {
+            let (x2, y2) = points[(i + 1) % points.len()];
+
+            line_pixels(self.antialias, x1, y1, x2, y2, |x, y, coverage| {
+                let distance = start + line_distance(x1, y1, x2, y2, x, y);
+
+                if (x, y) != (x2, y2)
+                    && dash
+                        .as_ref()
+                        .map_or(true, |dash| dash.is_on(distance as f32))
+                {
+                    self.blend_coverage(x, y, Layer::Outline, coverage);
+                }
+            });
+
+            start += line_distance(x1, y1, x2, y2, x2, y2);
+        }
+    }
+}
+
+/// Scanline polygon fill, sampling each pixel at its center.
+///
+/// Calls `f(y, x0, x1)` for every inclusive run of covered pixels within `clip`.
+pub(super) fn for_each_span(
+    contours: &[Vec<(f32, f32)>],
+    rule: FillRule,
+    clip: Rect,
+    mut f: impl FnMut(i32, i32, i32),
+) {
+    let edges = Edge::from_contours(contours);
+
+    if edges.is_empty() || clip.is_empty() {
+        return;
+    }
+
+    let min_y = edges.iter().map(|e| e.y0).fold(f32::INFINITY, f32::min);
+    let max_y = edges.iter().map(|e| e.y1).fold(f32::NEG_INFINITY, f32::max);
+
+    let first_row = (min_y.ceil() as i32).max(clip.y);
+    let last_row = (max_y.ceil() as i32 - 1).min(clip.y + clip.h - 1);
+
+    let mut crossings = Vec::new();
+
+    for y in first_row..=last_row {
+        crossings_at(&edges, y as f32, &mut crossings);
+
+        let mut winding = 0;
+
+        for pair in crossings.windows(2) {
+            winding += pair[0].1;
+
+            let inside = match rule {
+                FillRule::NonZero => winding != 0,
+                FillRule::EvenOdd => winding % 2 != 0,
+            };
+
+            if !inside {
+                continue;
+            }
+
+            let x0 = (pair[0].0.ceil() as i32).max(clip.x);
+            let x1 = (pair[1].0.ceil() as i32 - 1).min(clip.x + clip.w - 1);
+
+            if x0 <= x1 {
+                f(y, x0, x1);
+            }
+        }
+    }
+}
+
+/// Collects where the edges cross the horizontal line at `y`, as `(x, winding)` sorted by `x`.
+pub(super) fn crossings_at(edges: &[Edge], y: f32, crossings: &mut Vec<(f32, i32)>) {
+    crossings.clear();
+    crossings.extend(
+        edges
+            .iter()
+            .filter(|e| e.y0 <= y && y < e.y1)
+            .map(|e| (e.x_at(y), e.winding)),
+    );
+    crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+}
+
+/// A non-horizontal polygon edge, stored top to bottom.
+pub(super) struct Edge {
+    x0: f32,
+    pub(super) y0: f32,
+    pub(super) y1: f32,
+    dxdy: f32,
+    winding: i32,
+}
+
+impl Edge {
+    pub(super) fn from_contours(contours: &[Vec<(f32, f32)>]) -> Vec<Self> {
+        contours
+            .iter()
+            .filter(|contour| contour.len() >= 2)
+            .flat_map(|contour| {
+                let next = contour.iter().cycle().skip(1);
+                contour
+                    .iter()
+                    .zip(next)
+                    .filter_map(|(&a, &b)| Edge::new(a, b))
+            })
+            .collect()
+    }
+
+    fn new((ax, ay): (f32, f32), (bx, by): (f32, f32)) -> Option<Self> {
+        if ay == by {
+            return None;
+        }
+
+        let ((x0, y0), (x1, y1), winding) = if ay < by {
+            ((ax, ay), (bx, by), 1)
+        } else {
+            ((bx, by), (ax, ay), -1)
+        };
+
+        Some(Self {
+            x0,
+            y0,
+            y1,
+            dxdy: (x1 - x0) / (y1 - y0),
+            winding,
+        })
+    }
+
+    fn x_at(&self, y: f32) -> f32 {
+        self.x0 + (y - self.y0) * self.dxdy
+    }
+
+    pub(super) fn min_x(&self) -> f32 {
+        self.x0.min(self.x_at(self.y1))
+    }
+
+    pub(super) fn max_x(&self) -> f32 {
+        self.x0.max(self.x_at(self.y1))
+    }
+}
@@ -0,0 +1,200 @@
+use std::f32::consts::FRAC_PI_2;
+
+use super::ellipse::circle_extents;
+use super::{is_transparent, FillRule, Layer, Shape};
+use crate::Canvas;
+
+impl<'a, C> Shape<'a, C>
+where
+    C: Canvas,
+{
+    /// Same as `rect`, with all corners rounded with the given radius.
+    pub fn rounded_rect(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, radius: i32) {
+        self.rounded_rect_corners(x1, y1, x2, y2, [radius; 4]);
+    }
+
+    /// Same as `rect`, with each corner rounded by its own radius, starting at the top left
+    /// and going clockwise.
+    ///
+    /// The corners are quarters of midpoint circles, so equal radii give symmetric corners.
+    /// Radii are limited to half the rect's width and height.
+    pub fn rounded_rect_corners(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, radii: [i32; 4]) {
+        if x2 < x1 || y2 < y1 {
+            return;
+        }
+
+        self.set_origin([(x1, y1)]);
+
+        let max_radius = (x2 - x1).min(y2 - y1) / 2;
+        let radii = radii.map(|r| r.clamp(0, max_radius));
+
+        let draw_fill = !is_transparent(&self.fill);
+        let draw_outline = !is_transparent(&self.outline) && self.outline != self.fill;
+
+        if self.antialias {
+            let width = self.stroke_width.max(1) as f32;
+
+            if draw_fill {
+                let inset = if draw_outline { width } else { 0.0 };
+                let contour = rounded_rect_contour(x1, y1, x2, y2, radii, inset);
+
+                self.fill_contours(&[contour], FillRule::NonZero, Layer::Fill);
+            }
+
+            if draw_outline {
+                let contour = rounded_rect_contour(x1, y1, x2, y2, radii, width / 2.0);
+                self.stroke_path(&contour, true);
+            }
+
+            return;
+        }
+
+        let outer = RowSpans::new(x1, y1, x2, y2, radii);
+
+        if !draw_outline {
+            if draw_fill {
+                for (y, x0, x1) in outer.iter() {
+                    self.paint_span(Layer::Fill, y, x0, x1);
+                }
+            }
+
+            return;
+        }
+
+        let width = self.stroke_width.max(1);
+        let dashed = self.dash_pattern().is_some();
+
+        let inner = (width > 1).then(|| {
+            let radii = radii.map(|r| (r - width).max(0));
+            RowSpans::new(x1 + width, y1 + width, x2 - width, y2 - width, radii)
+        });
+
+        for (y, left, right) in outer.iter() {
+            // Without a stroke width, the outline is made of the pixels with a horizontal or
+            // vertical neighbour outside the shape
+            let (inner_left, inner_right) = match &inner {
+                Some(inner) => inner.get(y).unwrap_or((left, left - 1)),
+                None => match (outer.get(y - 1), outer.get(y + 1)) {
+                    (Some(above), Some(below)) => (
+                        (left + 1).max(above.0).max(below.0),
+                        (right - 1).min(above.1).min(below.1),
+                    ),
+                    _ => (left, left - 1),
+                },
+            };
+
+            if inner_left > inner_right {
+                if !dashed {
+                    self.paint_span(Layer::Outline, y, left, right);
+                }
+
+                continue;
+            }
+
+            if draw_fill {
+                self.paint_span(Layer::Fill, y, inner_left, inner_right);
+            }
+
+            if !dashed {
+                self.paint_span(Layer::Outline, y, left, inner_left - 1);
+                self.paint_span(Layer::Outline, y, inner_right + 1, right);
+            }
+        }
+
+        if dashed {
+            // Run along the middle of the outline
+            let contour = rounded_rect_contour(x1, y1, x2, y2, radii, width as f32 / 2.0);
+            self.stroke_path(&contour, true);
+        }
+    }
+}
+
+/// The horizontal extent of each row of a rounded rect.
+struct RowSpans {
+    y1: i32,
+    spans: Vec<(i32, i32)>,
+}
+
+impl RowSpans {
+    fn new(x1: i32, y1: i32, x2: i32, y2: i32, radii: [i32; 4]) -> Self {
+        let [top_left, top_right, bottom_right, bottom_left] = radii.map(circle_extents);
+
+        // How far a corner pulls in the row `dy` above or below its center
+        let inset = |extents: &[i32], dy: i32| match usize::try_from(dy) {
+            Ok(row) if row < extents.len() => extents.len() as i32 - 1 - extents[row],
+            _ => 0,
+        };
+
+        let spans = (y1..=y2)
+            .map(|y| {
+                let top = |extents: &Vec<i32>| inset(extents, extents.len() as i32 - 1 - (y - y1));
+                let bottom =
+                    |extents: &Vec<i32>| inset(extents, extents.len() as i32 - 1 - (y2 - y));
+
+                let left = top(&top_left).max(bottom(&bottom_left));
+                let right = top(&top_right).max(bottom(&bottom_right));
+
+                (x1 + left, x2 - right)
+            })
+            .collect();
+
+        Self { y1, spans }
+    }
+
+    fn get(&self, y: i32) -> Option<(i32, i32)> {
+        usize::try_from(y - self.y1)
+            .ok()
+            .and_then(|row| self.spans.get(row).copied())
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (i32, i32, i32)> + '_ {
+        (self.y1..)
+            .zip(&self.spans)
+            .map(|(y, &(left, right))| (y, left, right))
+    }
+}
+
+/// Outline of a rounded rect's pixels, moved `inset` inwards.
+fn rounded_rect_contour(
+    x1: i32,
+    y1: i32,
+    x2: i32,
+    y2: i32,
+    radii: [i32; 4],
+    inset: f32,
+) -> Vec<(f32, f32)> {
+    let (left, top) = (x1 as f32 - 0.5 + inset, y1 as f32 - 0.5 + inset);
+    let (right, bottom) = (x2 as f32 + 0.5 - inset, y2 as f32 + 0.5 - inset);
+
+    // Square corners stay square
+    let [top_left, top_right, bottom_right, bottom_left] = radii.map(|r| match r {
+        0 => 0.0,
+        r => (r as f32 + 0.5 - inset).max(0.0),
+    });
+
+    // Clockwise from the top left corner, starting at the angle pointing away from it
+    let corners = [
+        (left + top_left, top + top_left, top_left, 2.0),
+        (right - top_right, top + top_right, top_right, 3.0),
+        (
+            right - bottom_right,
+            bottom - bottom_right,
+            bottom_right,
+            0.0,
+        ),
+        (left + bottom_left, bottom - bottom_left, bottom_left, 1.0),
+    ];
+
+    let mut contour = Vec::new();
+
+    for (cx, cy, r, quarter) in corners {
+        let segments = ((r * 2.0).ceil() as usize).clamp(1, 64);
+
+        for i in 0..=segments {
+            let angle = (quarter + i as f32 / segments as f32) * FRAC_PI_2;
+            contour.push((cx + r * angle.cos(), cy + r * angle.sin()));
+        }
+    }
+
+    contour
+}
@@ -0,0 +1,290 @@
+use std::f32::consts::TAU;
+
+use super::{is_transparent, line_distance, line_pixels, FillRule, Layer, Shape};
+use crate::Canvas;
+
+type Point = (f32, f32);
+
+/// How the ends of open lines wider than a pixel are drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineCap {
+    /// The line stops exactly at its end points.
+    #[default]
+    Butt,
+    /// The line extends half its width past its end points.
+    Square,
+    /// The line ends in a half circle around its end points.
+    Round,
+}
+
+/// How the corners of lines wider than a pixel are drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineJoin {
+    /// Sharp corners, falling back to `Bevel` for very acute angles.
+    #[default]
+    Miter,
+    /// Corners cut off flat.
+    Bevel,
+    /// Rounded corners.
+    Round,
+}
+
+/// Corners sharper than this ratio of miter length to stroke width get beveled.
+const MITER_LIMIT: f32 = 4.0;
+
+impl<'a, C> Shape<'a, C>
+where
+    C: Canvas,
+{
+    /// Draws connected lines through `points` with the outline color.
+    pub fn polyline(&mut self, points: &[(i32, i32)]) {
+        self.set_origin(points.iter().copied());
+
+        if self.stroke_width > 1 {
+            self.stroke(points, false);
+            return;
+        }
+
+        if is_transparent(&self.outline) {
+            return;
+        }
+
+        if let [(x, y)] = points {
+            self.paint_pixel(Layer::Outline, *x, *y);
+            return;
+        }
+
+        let dash = self.dash_pattern();
+        let mut start = 0;
+
+        for (i, pair) in points.windows(2).enumerate() {
+            let (x1, y1) = pair[0];
+            let (x2, y2) = pair[1];
+            let is_last = i == points.len() - 2;
+
+            // Skip the shared points so translucent lines don't blend them twice
+            line_pixels(self.antialias, x1, y1, x2, y2, |x, y, coverage| {
+                let distance = start + line_distance(x1, y1, x2, y2, x, y);
+
+                if (is_last || (x, y) != (x2, y2))
+                    && dash
+                        .as_ref()
+                        .map_or(true, |dash| dash.is_on(distance as f32))
+                {
+                    self.blend_coverage(x, y, Layer::Outline, coverage);
+                }
+            });
+
+            start += line_distance(x1, y1, x2, y2, x2, y2);
+        }
+    }
+
+    /// Fills the outline of a path wider than a pixel with the outline color.
+    pub(super) fn stroke(&mut self, points: &[(i32, i32)], closed: bool) {
+        let points: Vec<_> = points.iter().map(|&(x, y)| (x as f32, y as f32)).collect();
+
+        self.stroke_path(&points, closed);
+    }
+
+    /// Same as `stroke`, for paths that don't run through pixel centers.
+    pub(super) fn stroke_path(&mut self, points: &[Point], closed: bool) {
+        if is_transparent(&self.outline) {
+            return;
+        }
+
+        let contours = self.outline_contours(points, closed);
+
+        // All contours wind the same way, so non-zero filling draws their union
+        self.fill_contours(&contours, FillRule::NonZero, Layer::Outline);
+    }
+
+    /// Polygons covering the stroke of a path in the current style, dashes included.
+    pub(super) fn outline_contours(&self, points: &[Point], closed: bool) -> Vec<Vec<Point>> {
+        let width = self.stroke_width.max(1) as f32;
+
+        match self.dash_pattern() {
+            Some(dash) => dash
+                .split(points, closed)
+                .iter()
+                .flat_map(|dash| stroke_contours(dash, false, width, self.line_cap, self.line_join))
+                .collect(),
+            None => stroke_contours(points, closed, width, self.line_cap, self.line_join),
+        }
+    }
+}
+
+/// Turns a path into polygons that together cover its stroke.
+///
+/// All returned contours are wound the same way.
+pub(super) fn stroke_contours(
+    points: &[Point],
+    closed: bool,
+    width: f32,
+    cap: LineCap,
+    join: LineJoin,
+) -> Vec<Vec<Point>> {
+    let half = width / 2.0;
+
+    let mut points = points.to_vec();
+    points.dedup();
+
+    if closed && points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+
+    let mut contours = Vec::new();
+
+    match points.as_slice() {
+        [] => return contours,
+        [point] => {
+            if !closed {
+                match cap {
+                    LineCap::Butt => {}
+                    LineCap::Square => contours.push(vec![
+                        (point.0 - half, point.1 - half),
+                        (point.0 + half, point.1 - half),
+                        (point.0 + half, point.1 + half),
+                        (point.0 - half, point.1 + half),
+                    ]),
+                    LineCap::Round => contours.push(circle_contour(*point, half)),
+                }
+            }
+
+            return contours;
+        }
+        _ => {}
+    }
+
+    let segment_count = if closed {
+        points.len()
+    } else {
+        points.len() - 1
+    };
+
+    for i in 0..segment_count {
+        let mut p = points[i];
+        let mut q = points[(i + 1) % points.len()];
+        let (dx, dy) = direction(p, q);
+
+        if !closed && cap == LineCap::Square {
+            if i == 0 {
+                p = (p.0 - dx * half, p.1 - dy * half);
+            }
+
+            if i == segment_count - 1 {
+                q = (q.0 + dx * half, q.1 + dy * half);
+            }
+        }
+
+        let (nx, ny) = (-dy * half, dx * half);
+
+        contours.push(vec![
+            (p.0 + nx, p.1 + ny),
+            (q.0 + nx, q.1 + ny),
+            (q.0 - nx, q.1 - ny),
+            (p.0 - nx, p.1 - ny),
+        ]);
+    }
+
+    if !closed && cap == LineCap::Round {
+        contours.push(circle_contour(points[0], half));
+        contours.push(circle_contour(points[points.len() - 1], half));
+    }
+
+    let joints = if closed {
+        0..points.len()
+    } else {
+        1..points.len() - 1
+    };
+
+    for i in joints {
+        let prev = points[(i + points.len() - 1) % points.len()];
+        let point = points[i];
+        let next = points[(i + 1) % points.len()];
+
+        join_contours(prev, point, next, half, join, &mut contours);
+    }
+
+    for contour in &mut contours {
+        if signed_area(contour) < 0.0 {
+            contour.reverse();
+        }
+    }
+
+    contours
+}
+
+/// Adds the polygons filling the gap between two segments meeting at `point`.
+fn join_contours(
+    prev: Point,
+    point: Point,
+    next: Point,
+    half: f32,
+    join: LineJoin,
+    contours: &mut Vec<Vec<Point>>,
+) {
+    if join == LineJoin::Round {
+        contours.push(circle_contour(point, half));
+        return;
+    }
+
+    let (dx0, dy0) = direction(prev, point);
+    let (dx1, dy1) = direction(point, next);
+
+    // Normals of both segments, scaled to the stroke's half width.
+    // Both sides are filled, as the inner one is covered by the segments anyway.
+    for side in [1.0, -1.0] {
+        let n0 = (-dy0 * half * side, dx0 * half * side);
+        let n1 = (-dy1 * half * side, dx1 * half * side);
+
+        let a = (point.0 + n0.0, point.1 + n0.1);
+        let b = (point.0 + n1.0, point.1 + n1.1);
+
+        let bisector = (n0.0 + n1.0, n0.1 + n1.1);
+        let bisector_len = (bisector.0 * bisector.0 + bisector.1 * bisector.1).sqrt();
+
+        // Cosine of half the angle between the normals
+        let cos = bisector_len / (2.0 * half);
+
+        if join == LineJoin::Miter && cos > 1.0 / MITER_LIMIT {
+            let miter_len = half / cos;
+            let tip = (
+                point.0 + bisector.0 / bisector_len * miter_len,
+                point.1 + bisector.1 / bisector_len * miter_len,
+            );
+
+            contours.push(vec![point, a, tip, b]);
+        } else {
+            contours.push(vec![point, a, b]);
+        }
+    }
+}
+
+fn direction(p: Point, q: Point) -> Point {
+    let (dx, dy) = (q.0 - p.0, q.1 - p.1);
+    let len = (dx * dx + dy * dy).sqrt();
+
+    (dx / len, dy / len)
+}
+
+pub(super) fn circle_contour(center: Point, r: f32) -> Vec<Point> {
+    let segments = ((r * 4.0).ceil() as usize).clamp(8, 128);
+
+    (0..segments)
+        .map(|i| {
+            let angle = i as f32 / segments as f32 * TAU;
+            (center.0 + r * angle.cos(), center.1 + r * angle.sin())
+        })
+        .collect()
+}
+
+fn signed_area(contour: &[Point]) -> f32 {
+    let next = contour.iter().cycle().skip(1);
+
+    contour
+        .iter()
+        .zip(next)
+        .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+        .sum::<f32>()
+        / 2.0
+}
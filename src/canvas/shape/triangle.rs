@@ -0,0 +1,127 @@
+use super::{is_transparent, FillRule, Layer, Shape};
+use crate::{Canvas, Color, Rect};
+
+type Point = (i32, i32);
+
+impl<'a, C> Shape<'a, C>
+where
+    C: Canvas,
+{
+    /// Draws a triangle using the top-left fill rule, so triangles sharing an edge never
+    /// cover the same pixel twice.
+    pub fn triangle(&mut self, a: Point, b: Point, c: Point) {
+        self.set_origin([a, b, c]);
+
+        if !is_transparent(&self.fill) {
+            if self.antialias {
+                let contour = [a, b, c].map(|(x, y)| (x as f32, y as f32)).to_vec();
+                self.fill_contours(&[contour], FillRule::NonZero, Layer::Fill);
+            } else {
+                let clip = self.canvas.clip_rect();
+
+                for_each_triangle_span(a, b, c, clip, |y, x0, x1| {
+                    self.paint_span(Layer::Fill, y, x0, x1);
+                });
+            }
+        }
+
+        if !is_transparent(&self.outline) {
+            self.closed_outline(&[a, b, c]);
+        }
+    }
+
+    /// Same as `triangle`, but the fill blends between a color at each vertex.
+    pub fn shaded_triangle(&mut self, a: Point, b: Point, c: Point, colors: [Color; 3]) {
+        self.set_origin([a, b, c]);
+
+        let area = edge(a, b, c) as f32;
+
+        if area != 0.0 {
+            let [ca, cb, cc] = colors.map(|color| color.as_rgba_f32());
+            let clip = self.canvas.clip_rect();
+
+            for_each_triangle_span(a, b, c, clip, |y, x0, x1| {
+                for x in x0..=x1 {
+                    let wa = edge(b, c, (x, y)) as f32 / area;
+                    let wb = edge(c, a, (x, y)) as f32 / area;
+                    let wc = edge(a, b, (x, y)) as f32 / area;
+
+                    let [r, g, b, a] =
+                        std::array::from_fn(|i| ca[i] * wa + cb[i] * wb + cc[i] * wc);
+
+                    self.canvas.blend(x, y, Color::rgba(r, g, b, a), self.blend);
+                }
+            });
+        }
+
+        if !is_transparent(&self.outline) {
+            self.closed_outline(&[a, b, c]);
+        }
+    }
+}
+
+/// Calls `f(y, x0, x1)` for every row of pixels whose centers lie in the triangle.
+///
+/// Pixels exactly on an edge are only covered if it's a top or a left edge.
+fn for_each_triangle_span(
+    a: Point,
+    b: Point,
+    c: Point,
+    clip: Rect,
+    mut f: impl FnMut(i32, i32, i32),
+) {
+    // Make the vertices go clockwise on screen, so the inside is where all edge functions are positive
+    let (b, c) = match edge(a, b, c) {
+        0 => return,
+        area if area < 0 => (c, b),
+        _ => (b, c),
+    };
+
+    let edges = [(a, b), (b, c), (c, a)];
+
+    let min_y = a.1.min(b.1).min(c.1).max(clip.y);
+    let max_y = a.1.max(b.1).max(c.1).min(clip.y + clip.h - 1);
+
+    for y in min_y..=max_y {
+        let mut x0 = clip.x as i64;
+        let mut x1 = (clip.x + clip.w - 1) as i64;
+
+        for &(p, q) in &edges {
+            let (px, py) = (p.0 as i64, p.1 as i64);
+            let (qx, qy) = (q.0 as i64, q.1 as i64);
+
+            // The edge function for this row is `k - dy * x`, which has to reach `min`
+            let dy = qy - py;
+            let k = (qx - px) * (y as i64 - py) + dy * px;
+            let min = if is_top_left(p, q) { 0 } else { 1 };
+
+            if dy == 0 {
+                if k < min {
+                    x1 = x0 - 1;
+                }
+            } else if dy > 0 {
+                x1 = x1.min((k - min).div_euclid(dy));
+            } else {
+                x0 = x0.max(-(k - min).div_euclid(-dy));
+            }
+        }
+
+        if x0 <= x1 {
+            f(y, x0 as i32, x1 as i32);
+        }
+    }
+}
+
+/// Twice the signed area of `a`, `b`, `p`, positive if `p` is to the right of `a -> b` on screen.
+fn edge(a: Point, b: Point, p: Point) -> i64 {
+    let (ax, ay) = (a.0 as i64, a.1 as i64);
+    let (bx, by) = (b.0 as i64, b.1 as i64);
+    let (px, py) = (p.0 as i64, p.1 as i64);
+
+    (bx - ax) * (py - ay) - (by - ay) * (px - ax)
+}
+
+/// For clockwise triangles a top edge goes right horizontally and a left edge goes up.
+fn is_top_left(p: Point, q: Point) -> bool {
+    (p.1 == q.1 && q.0 > p.0) || q.1 < p.1
+}
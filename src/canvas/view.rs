@@ -0,0 +1,107 @@
+use crate::canvas::{Canvas, ReadCanvas};
+use crate::{BlendMode, Color, Rect};
+
+/// A rectangular region of another canvas with its own `(0, 0)` origin.
+///
+/// Anything drawn outside the region is clipped.
+pub struct View<'a, C> {
+    canvas: &'a mut C,
+    rect: Rect,
+    clip_stack: Vec<Rect>,
+}
+
+impl<'a, C> View<'a, C> {
+    pub fn new(canvas: &'a mut C, x: i32, y: i32, w: i32, h: i32) -> Self {
+        Self {
+            canvas,
+            rect: Rect::new(x, y, w, h),
+            clip_stack: Vec::new(),
+        }
+    }
+}
+
+impl<'a, C> View<'a, C>
+where
+    C: Canvas,
+{
+    fn to_parent(&self, rect: Rect) -> Rect {
+        Rect::new(rect.x + self.rect.x, rect.y + self.rect.y, rect.w, rect.h)
+    }
+
+    fn to_local(&self, rect: Rect) -> Rect {
+        Rect::new(rect.x - self.rect.x, rect.y - self.rect.y, rect.w, rect.h)
+    }
+}
+
+impl<'a, C> Canvas for View<'a, C>
+where
+    C: Canvas,
+{
+    fn blend(&mut self, x: i32, y: i32, color: Color, mode: BlendMode) {
+        if !self.clip_rect().contains(x, y) {
+            return;
+        }
+
+        self.canvas
+            .blend(x + self.rect.x, y + self.rect.y, color, mode);
+    }
+
+    fn blend_span(&mut self, y: i32, x0: i32, x1: i32, color: Color, mode: BlendMode) {
+        self.blend_rect(x0, y, x1, y, color, mode);
+    }
+
+    fn blend_rect(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: Color, mode: BlendMode) {
+        let rect = self.clip_rect().clamp_corners(x0, y0, x1, y1);
+
+        if rect.is_empty() {
+            return;
+        }
+
+        let rect = self.to_parent(rect);
+
+        self.canvas.blend_rect(
+            rect.x,
+            rect.y,
+            rect.x + rect.w - 1,
+            rect.y + rect.h - 1,
+            color,
+            mode,
+        );
+    }
+
+    fn size(&self) -> (i32, i32) {
+        (self.rect.w, self.rect.h)
+    }
+
+    fn push_clip(&mut self, rect: Rect) {
+        let clip = self.clip_rect().intersect(rect);
+        self.clip_stack.push(clip);
+    }
+
+    fn pop_clip(&mut self) {
+        self.clip_stack.pop();
+    }
+
+    fn clip_rect(&self) -> Rect {
+        let parent_clip = self.to_local(self.canvas.clip_rect());
+        let bounds = Rect::from_size(self.size()).intersect(parent_clip);
+
+        match self.clip_stack.last() {
+            Some(clip) => bounds.intersect(*clip),
+            None => bounds,
+        }
+    }
+}
+
+impl<'a, C> ReadCanvas for View<'a, C>
+where
+    C: ReadCanvas,
+{
+    fn get(&self, x: i32, y: i32) -> Option<Color> {
+        if !Rect::from_size(self.size()).contains(x, y) {
+            return None;
+        }
+
+        self.canvas.get(x + self.rect.x, y + self.rect.y)
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{Canvas, Color, PixelBuffer};
+
+/// An owned RGBA8 image, usable as an offscreen canvas and as a `Blit` source.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    width: i32,
+    height: i32,
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    /// Creates a fully transparent image.
+    ///
+    /// Panics if `width` or `height` is negative.
+    pub fn new(width: i32, height: i32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; byte_len(width, height)],
+        }
+    }
+
+    pub fn filled(width: i32, height: i32, color: Color) -> Self {
+        let mut image = Self::new(width, height);
+        image.as_pixel_buffer().clear(color);
+        image
+    }
+
+    /// Wraps tightly packed RGBA8 pixel data.
+    ///
+    /// Panics if `width` or `height` is negative, or `pixels` doesn't hold exactly
+    /// `width * height` pixels.
+    pub fn from_vec(width: i32, height: i32, pixels: Vec<u8>) -> Self {
+        assert_eq!(
+            pixels.len(),
+            byte_len(width, height),
+            "Pixel data doesn't match a {width}x{height} image"
+        );
+
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    pub fn size(&self) -> (i32, i32) {
+        (self.width, self.height)
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn pixels_mut(&mut self) -> &mut [u8] {
+        &mut self.pixels
+    }
+
+    pub fn into_vec(self) -> Vec<u8> {
+        self.pixels
+    }
+
+    pub fn as_pixel_buffer(&mut self) -> PixelBuffer<'_> {
+        // The height can't be worked out from the pixels of a zero width image
+        PixelBuffer::with_stride(
+            self.width,
+            self.height,
+            self.width as usize * 4,
+            &mut self.pixels,
+        )
+    }
+}
+
+/// Size of the RGBA8 pixel data of a `width` by `height` image.
+fn byte_len(width: i32, height: i32) -> usize {
+    assert!(
+        width >= 0 && height >= 0,
+        "Image size can't be negative, got {width}x{height}"
+    );
+
+    width as usize * height as usize * 4
+}
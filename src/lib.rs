@@ -1,5 +1,11 @@
+mod blend;
 mod canvas;
+mod image;
+mod paint;
 mod pixel_buffer;
+pub mod pixel_format;
+mod rect;
+mod srgb;
 mod text_canvas;
 
 #[cfg(not(feature = "bevy_ext"))]
@@ -8,9 +14,17 @@ mod color;
 #[cfg(feature = "bevy_ext")]
 pub use bevy::prelude::Color;
 
-pub use self::canvas::blit::Blit;
-pub use self::canvas::Canvas;
+pub use self::blend::{AlphaMode, BlendMode};
+pub use self::canvas::blit::{Blit, Filter};
+pub use self::canvas::flood_fill::{Connectivity, FloodFill};
+pub use self::canvas::shape::{FillRule, LineCap, LineJoin, Path};
+pub use self::canvas::view::View;
+pub use self::canvas::{Canvas, ReadCanvas};
 #[cfg(not(feature = "bevy_ext"))]
 pub use self::color::Color;
+pub use self::image::Image;
+pub use self::paint::{Anchor, Gradient, Paint, Pattern, Spread};
 pub use self::pixel_buffer::PixelBuffer;
+pub use self::pixel_format::PixelFormat;
+pub use self::rect::Rect;
 pub use self::text_canvas::TextCanvas;
@@ -0,0 +1,215 @@
+use std::f32::consts::TAU;
+
+use crate::blend;
+use crate::Color;
+
+mod pattern;
+
+pub use self::pattern::{Anchor, Pattern};
+
+/// What shapes are filled and outlined with.
+///
+/// Anything taking a `Paint` also takes a `Color`, a `Gradient` or a `Pattern`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Paint {
+    Solid(Color),
+    Gradient(Gradient),
+    Pattern(Pattern),
+}
+
+impl Paint {
+    /// The color at a position on the canvas.
+    pub fn color_at(&self, x: f32, y: f32) -> Color {
+        match self {
+            Paint::Solid(color) => *color,
+            Paint::Gradient(gradient) => gradient.color_at(x, y),
+            Paint::Pattern(pattern) => pattern.color_at(x.floor() as i32, y.floor() as i32),
+        }
+    }
+
+    /// Where the paint's origin is, only patterns can follow the shape.
+    pub(crate) fn anchor(&self) -> Anchor {
+        match self {
+            Paint::Pattern(pattern) => pattern.anchored_to(),
+            Paint::Solid(_) | Paint::Gradient(_) => Anchor::Canvas,
+        }
+    }
+
+    /// Returns the color if it's the same everywhere.
+    pub(crate) fn as_solid(&self) -> Option<Color> {
+        match self {
+            Paint::Solid(color) => Some(*color),
+            Paint::Gradient(_) | Paint::Pattern(_) => None,
+        }
+    }
+
+    pub(crate) fn is_transparent(&self) -> bool {
+        match self {
+            Paint::Solid(color) => color.a() == 0.0,
+            Paint::Gradient(gradient) => gradient.stops.iter().all(|(_, color)| color.a() == 0.0),
+            Paint::Pattern(pattern) => pattern.is_transparent(),
+        }
+    }
+}
+
+impl From<Color> for Paint {
+    fn from(color: Color) -> Self {
+        Paint::Solid(color)
+    }
+}
+
+impl From<Gradient> for Paint {
+    fn from(gradient: Gradient) -> Self {
+        Paint::Gradient(gradient)
+    }
+}
+
+impl From<Pattern> for Paint {
+    fn from(pattern: Pattern) -> Self {
+        Paint::Pattern(pattern)
+    }
+}
+
+/// How a gradient continues past its first and last stop.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Spread {
+    /// Keeps the color of the nearest end.
+    #[default]
+    Pad,
+    /// Starts over from the first stop.
+    Repeat,
+    /// Goes back and forth between the ends.
+    Reflect,
+}
+
+/// Colors blending into each other along a line, around a center or around an angle.
+///
+/// Positions are in canvas coordinates and stops are placed between 0 and 1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gradient {
+    geometry: Geometry,
+    stops: Vec<(f32, Color)>,
+    spread: Spread,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Geometry {
+    Linear { start: (f32, f32), end: (f32, f32) },
+    Radial { center: (f32, f32), radius: f32 },
+    Conic { center: (f32, f32), angle: f32 },
+}
+
+impl Gradient {
+    /// Goes from the first stop at `start` to the last stop at `end`, constant across the line between them.
+    pub fn linear(start: (f32, f32), end: (f32, f32)) -> Self {
+        Self::new(Geometry::Linear { start, end })
+    }
+
+    /// Goes from the first stop at `center` to the last stop `radius` pixels away from it.
+    pub fn radial(center: (f32, f32), radius: f32) -> Self {
+        Self::new(Geometry::Radial { center, radius })
+    }
+
+    /// Sweeps clockwise around `center`, starting at `angle` radians from the positive x axis.
+    pub fn conic(center: (f32, f32), angle: f32) -> Self {
+        Self::new(Geometry::Conic { center, angle })
+    }
+
+    fn new(geometry: Geometry) -> Self {
+        Self {
+            geometry,
+            stops: Vec::new(),
+            spread: Spread::Pad,
+        }
+    }
+
+    /// Adds a color at `offset` between 0 and 1.
+    ///
+    /// Stops can be added in any order. Two stops at the same offset make a hard edge.
+    pub fn stop(mut self, offset: f32, color: Color) -> Self {
+        self.add_stop(offset, color);
+        self
+    }
+
+    pub fn add_stop(&mut self, offset: f32, color: Color) {
+        let offset = offset.clamp(0.0, 1.0);
+        let index = self.stops.partition_point(|&(other, _)| other <= offset);
+
+        self.stops.insert(index, (offset, color));
+    }
+
+    pub fn spread(mut self, spread: Spread) -> Self {
+        self.spread = spread;
+        self
+    }
+
+    pub fn set_spread(&mut self, spread: Spread) {
+        self.spread = spread;
+    }
+
+    /// The color at a position on the canvas.
+    pub fn color_at(&self, x: f32, y: f32) -> Color {
+        let t = match self.geometry {
+            Geometry::Linear { start, end } => {
+                let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+                let len2 = dx * dx + dy * dy;
+
+                if len2 == 0.0 {
+                    0.0
+                } else {
+                    ((x - start.0) * dx + (y - start.1) * dy) / len2
+                }
+            }
+            Geometry::Radial { center, radius } => {
+                let distance = ((x - center.0).powi(2) + (y - center.1).powi(2)).sqrt();
+
+                if radius <= 0.0 {
+                    1.0
+                } else {
+                    distance / radius
+                }
+            }
+            Geometry::Conic { center, angle } => {
+                ((y - center.1).atan2(x - center.0) - angle).rem_euclid(TAU) / TAU
+            }
+        };
+
+        self.color_at_offset(self.spread_offset(t))
+    }
+
+    fn spread_offset(&self, t: f32) -> f32 {
+        match self.spread {
+            Spread::Pad => t.clamp(0.0, 1.0),
+            Spread::Repeat => t.rem_euclid(1.0),
+            Spread::Reflect => 1.0 - (t.rem_euclid(2.0) - 1.0).abs(),
+        }
+    }
+
+    fn color_at_offset(&self, t: f32) -> Color {
+        let (first, last) = match (self.stops.first(), self.stops.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Color::rgba(0.0, 0.0, 0.0, 0.0),
+        };
+
+        if t <= first.0 {
+            return first.1;
+        }
+
+        if t >= last.0 {
+            return last.1;
+        }
+
+        let next = self.stops.partition_point(|&(offset, _)| offset <= t);
+        let (a_offset, a) = self.stops[next - 1];
+        let (b_offset, b) = self.stops[next];
+
+        let f = (t - a_offset) / (b_offset - a_offset);
+
+        // Interpolate premultiplied so fading into transparent doesn't pick up its color
+        let a = blend::premultiply(a.as_rgba_f32());
+        let b = blend::premultiply(b.as_rgba_f32());
+        let [r, g, b, a] = blend::unpremultiply(std::array::from_fn(|i| a[i] + (b[i] - a[i]) * f));
+
+        Color::rgba(r, g, b, a)
+    }
+}
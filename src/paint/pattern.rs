@@ -0,0 +1,194 @@
+use crate::{Color, Image};
+
+/// A repeating tile of pixels, either from an image or one of the built-in patterns.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pattern {
+    tile: Tile,
+    anchor: Anchor,
+}
+
+/// Where a pattern's tiles start.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Anchor {
+    /// Tiles line up across the whole canvas, so neighbouring shapes continue the same pattern.
+    #[default]
+    Canvas,
+    /// Tiles start at the top left corner of each shape's bounds, so the pattern moves with it.
+    Shape,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Tile {
+    Image {
+        image: Image,
+        /// Whether every pixel is fully transparent, worked out once since shapes check it on each draw.
+        transparent: bool,
+    },
+    Checkerboard {
+        size: i32,
+        colors: [Color; 2],
+    },
+    Stripes {
+        width: i32,
+        direction: Direction,
+        colors: [Color; 2],
+    },
+    Crosshatch {
+        spacing: i32,
+        colors: [Color; 2],
+    },
+    Dither {
+        level: f32,
+        colors: [Color; 2],
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Direction {
+    Horizontal,
+    Vertical,
+    Diagonal,
+}
+
+/// Thresholds of an ordered dither, spreading each level as evenly as possible.
+const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+impl Pattern {
+    /// Repeats the pixels of an image.
+    pub fn image(image: Image) -> Self {
+        let transparent = image.pixels().chunks_exact(4).all(|pixel| pixel[3] == 0);
+
+        Self::new(Tile::Image { image, transparent })
+    }
+
+    /// Alternates between `a` and `b` in squares of `size` pixels, starting with `a`.
+    pub fn checkerboard(size: i32, a: Color, b: Color) -> Self {
+        Self::new(Tile::Checkerboard {
+            size: size.max(1),
+            colors: [a, b],
+        })
+    }
+
+    /// Rows of `a` and `b`, each `width` pixels tall.
+    pub fn horizontal_stripes(width: i32, a: Color, b: Color) -> Self {
+        Self::stripes(width, Direction::Horizontal, a, b)
+    }
+
+    /// Columns of `a` and `b`, each `width` pixels wide.
+    pub fn vertical_stripes(width: i32, a: Color, b: Color) -> Self {
+        Self::stripes(width, Direction::Vertical, a, b)
+    }
+
+    /// Stripes of `a` and `b` running from the top right to the bottom left,
+    /// each `width` pixels wide when measured horizontally.
+    pub fn diagonal_stripes(width: i32, a: Color, b: Color) -> Self {
+        Self::stripes(width, Direction::Diagonal, a, b)
+    }
+
+    fn stripes(width: i32, direction: Direction, a: Color, b: Color) -> Self {
+        Self::new(Tile::Stripes {
+            width: width.max(1),
+            direction,
+            colors: [a, b],
+        })
+    }
+
+    /// One pixel wide diagonal lines in both directions, `spacing` pixels apart.
+    pub fn crosshatch(spacing: i32, line: Color, background: Color) -> Self {
+        Self::new(Tile::Crosshatch {
+            spacing: spacing.max(1),
+            colors: [line, background],
+        })
+    }
+
+    /// An ordered Bayer dither between `a` and `b`, using `a` for roughly `level` of the pixels.
+    ///
+    /// A level of 0.5 gives a checkerboard of single pixels.
+    pub fn dither(level: f32, a: Color, b: Color) -> Self {
+        Self::new(Tile::Dither {
+            level,
+            colors: [a, b],
+        })
+    }
+
+    fn new(tile: Tile) -> Self {
+        Self {
+            tile,
+            anchor: Anchor::Canvas,
+        }
+    }
+
+    pub fn anchor(mut self, anchor: Anchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    pub fn set_anchor(&mut self, anchor: Anchor) {
+        self.anchor = anchor;
+    }
+
+    pub(crate) fn anchored_to(&self) -> Anchor {
+        self.anchor
+    }
+
+    /// The color of the pixel at `(x, y)` relative to the pattern's anchor.
+    pub fn color_at(&self, x: i32, y: i32) -> Color {
+        let pick = |colors: &[Color; 2], first: bool| if first { colors[0] } else { colors[1] };
+
+        match &self.tile {
+            Tile::Image { image, .. } => {
+                if image.width() <= 0 || image.height() <= 0 {
+                    return Color::rgba(0.0, 0.0, 0.0, 0.0);
+                }
+
+                let x = x.rem_euclid(image.width()) as usize;
+                let y = y.rem_euclid(image.height()) as usize;
+                let index = (y * image.width() as usize + x) * 4;
+                let pixels = image.pixels();
+
+                Color::rgba_u8(
+                    pixels[index],
+                    pixels[index + 1],
+                    pixels[index + 2],
+                    pixels[index + 3],
+                )
+            }
+            Tile::Checkerboard { size, colors } => {
+                let cell = x.div_euclid(*size) + y.div_euclid(*size);
+                pick(colors, cell.rem_euclid(2) == 0)
+            }
+            Tile::Stripes {
+                width,
+                direction,
+                colors,
+            } => {
+                let position = match direction {
+                    Direction::Horizontal => y,
+                    Direction::Vertical => x,
+                    Direction::Diagonal => x + y,
+                };
+
+                pick(colors, position.div_euclid(*width).rem_euclid(2) == 0)
+            }
+            Tile::Crosshatch { spacing, colors } => {
+                let on_line =
+                    (x + y).rem_euclid(*spacing) == 0 || (x - y).rem_euclid(*spacing) == 0;
+                pick(colors, on_line)
+            }
+            Tile::Dither { level, colors } => {
+                let threshold = BAYER[y.rem_euclid(4) as usize][x.rem_euclid(4) as usize];
+                pick(colors, (threshold as f32 + 0.5) / 16.0 < *level)
+            }
+        }
+    }
+
+    pub(crate) fn is_transparent(&self) -> bool {
+        match &self.tile {
+            Tile::Image { transparent, .. } => *transparent,
+            Tile::Checkerboard { colors, .. }
+            | Tile::Stripes { colors, .. }
+            | Tile::Crosshatch { colors, .. }
+            | Tile::Dither { colors, .. } => colors.iter().all(|color| color.a() == 0.0),
+        }
+    }
+}
@@ -1,58 +1,378 @@
-use crate::canvas::Canvas;
-use crate::Color;
+use std::marker::PhantomData;
 
-pub struct PixelBuffer<'a> {
+use crate::blend::{self, AlphaMode};
+use crate::canvas::{Canvas, ReadCanvas};
+use crate::pixel_format::{PixelFormat, Rgba8, Xrgb8888, MAX_BYTES_PER_PIXEL};
+use crate::BlendMode;
+use crate::{srgb, Color, Rect};
+
+pub struct PixelBuffer<'a, F = Rgba8> {
     pub width: i32,
+    pub height: i32,
+    /// Distance between the starts of two consecutive rows, in bytes.
+    pub stride: usize,
     pub pixels: &'a mut [u8],
+    alpha_mode: AlphaMode,
+    linear_blending: bool,
+    clip_stack: Vec<Rect>,
+    format: PhantomData<F>,
 }
 
 impl<'a> PixelBuffer<'a> {
+    /// Wraps tightly packed RGBA8 rows, deriving the height from the length of `pixels`.
     pub fn new(width: i32, pixels: &'a mut [u8]) -> Self {
-        Self { width, pixels }
+        Self::from_bytes(width, pixels)
+    }
+
+    /// Wraps RGBA8 rows that are `stride` bytes apart, e.g. a GPU staging buffer with padded rows
+    /// or a sub-rectangle of a larger image.
+    ///
+    /// Panics if `pixels` is too short to hold `height` rows.
+    pub fn with_stride(width: i32, height: i32, stride: usize, pixels: &'a mut [u8]) -> Self {
+        Self::from_bytes_with_stride(width, height, stride, pixels)
+    }
+}
+
+impl<'a> PixelBuffer<'a, Xrgb8888> {
+    /// Wraps tightly packed `0x00RRGGBB` pixels.
+    pub fn from_u32(width: i32, pixels: &'a mut [u32]) -> Self {
+        let len = pixels.len() * 4;
+
+        // SAFETY: `u32` has no padding and a stricter alignment than `u8`,
+        // and the returned slice borrows `pixels` mutably for its whole lifetime.
+        let bytes = unsafe { std::slice::from_raw_parts_mut(pixels.as_mut_ptr() as *mut u8, len) };
+
+        Self::from_bytes(width, bytes)
     }
 }
 
-impl<'a> Canvas for PixelBuffer<'a> {
-    fn set(&mut self, x: i32, y: i32, color: Color) {
-        let [r, g, b, a] = color.as_rgba_f32();
+impl<'a, F> PixelBuffer<'a, F>
+where
+    F: PixelFormat,
+{
+    /// Same as `new`, for any pixel format.
+    pub fn from_bytes(width: i32, pixels: &'a mut [u8]) -> Self {
+        let stride = width as usize * F::BYTES_PER_PIXEL;
+        let height = (pixels.len() / stride) as i32;
+
+        Self::from_bytes_with_stride(width, height, stride, pixels)
+    }
+
+    /// Same as `with_stride`, for any pixel format.
+    pub fn from_bytes_with_stride(
+        width: i32,
+        height: i32,
+        stride: usize,
+        pixels: &'a mut [u8],
+    ) -> Self {
+        let row_len = width as usize * F::BYTES_PER_PIXEL;
+
+        assert!(stride >= row_len, "Stride is shorter than a row");
+
+        if height > 0 {
+            assert!(
+                pixels.len() >= (height as usize - 1) * stride + row_len,
+                "Pixel data is too short for a {width}x{height} buffer"
+            );
+        }
+
+        Self {
+            width,
+            height,
+            stride,
+            pixels,
+            alpha_mode: AlphaMode::Straight,
+            linear_blending: false,
+            clip_stack: Vec::new(),
+            format: PhantomData,
+        }
+    }
+
+    pub fn alpha_mode(mut self, alpha_mode: AlphaMode) -> Self {
+        self.alpha_mode = alpha_mode;
+        self
+    }
+
+    pub fn set_alpha_mode(&mut self, alpha_mode: AlphaMode) {
+        self.alpha_mode = alpha_mode;
+    }
+
+    /// Blend in linear light instead of directly on the sRGB encoded values.
+    ///
+    /// This is what `Rgba8UnormSrgb` textures expect and keeps antialiased edges from looking too dark.
+    pub fn linear_blending(mut self, enabled: bool) -> Self {
+        self.linear_blending = enabled;
+        self
+    }
+
+    pub fn set_linear_blending(&mut self, enabled: bool) {
+        self.linear_blending = enabled;
+    }
+
+    /// Converts a straight color into what's stored in the buffer.
+    fn encode(&self, rgba: [f32; 4]) -> [f32; 4] {
+        match self.alpha_mode {
+            AlphaMode::Straight => rgba,
+            AlphaMode::Premultiplied => blend::premultiply(rgba),
+        }
+    }
+
+    /// Converts a stored color back into a straight one.
+    fn decode(&self, rgba: [f32; 4]) -> [f32; 4] {
+        match self.alpha_mode {
+            AlphaMode::Straight => rgba,
+            AlphaMode::Premultiplied => blend::unpremultiply(rgba),
+        }
+    }
+
+    /// Blends a straight color onto a pixel that's known to be inside the buffer.
+    fn blend_pixel(&mut self, x: i32, y: i32, src: [f32; 4], mode: BlendMode) {
+        let dst = F::read(self.pixel(x, y));
+
+        let out = match (self.alpha_mode, mode, self.linear_blending) {
+            (AlphaMode::Premultiplied, BlendMode::SourceOver, false) => {
+                blend::source_over_premultiplied(blend::premultiply(src), dst)
+            }
+            (_, mode, false) => self.encode(mode.apply(src, self.decode(dst))),
+            (_, mode, true) => {
+                let src = srgb::to_linear(src);
+                let dst = srgb::to_linear(self.decode(dst));
+
+                self.encode(srgb::to_srgb(mode.apply(src, dst)))
+            }
+        };
+
+        F::write(self.pixel_mut(x, y), out);
+    }
+
+    fn pixel(&self, x: i32, y: i32) -> &[u8] {
+        let index = y as usize * self.stride + x as usize * F::BYTES_PER_PIXEL;
+
+        &self.pixels[index..index + F::BYTES_PER_PIXEL]
+    }
+
+    fn pixel_mut(&mut self, x: i32, y: i32) -> &mut [u8] {
+        let index = y as usize * self.stride + x as usize * F::BYTES_PER_PIXEL;
+
+        &mut self.pixels[index..index + F::BYTES_PER_PIXEL]
+    }
+}
+
+impl<'a, F> Canvas for PixelBuffer<'a, F>
+where
+    F: PixelFormat,
+{
+    fn blend(&mut self, x: i32, y: i32, color: Color, mode: BlendMode) {
+        if !self.clip_rect().contains(x, y) {
+            return;
+        }
+
+        self.blend_pixel(x, y, color.as_rgba_f32(), mode);
+    }
+
+    fn blend_span(&mut self, y: i32, x0: i32, x1: i32, color: Color, mode: BlendMode) {
+        self.blend_rect(x0, y, x1, y, color, mode);
+    }
+
+    fn blend_rect(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: Color, mode: BlendMode) {
+        let rect = self.clip_rect().clamp_corners(x0, y0, x1, y1);
+
+        if rect.is_empty() {
+            return;
+        }
+
+        let src = color.as_rgba_f32();
+        let is_opaque = src[3] >= 1.0;
 
-        let index = (y * self.width + x) as usize * 4;
+        let overwrites = match mode {
+            BlendMode::Replace => true,
+            BlendMode::SourceOver => is_opaque,
+            _ => false,
+        };
+
+        if !overwrites {
+            for y in rect.y..rect.y + rect.h {
+                for x in rect.x..rect.x + rect.w {
+                    self.blend_pixel(x, y, src, mode);
+                }
+            }
 
-        if index >= self.pixels.len() {
             return;
         }
 
-        let dest_r = self.pixels[index] as f32 / 255.0;
-        let dest_g = self.pixels[index + 1] as f32 / 255.0;
-        let dest_b = self.pixels[index + 2] as f32 / 255.0;
-        let dest_a = self.pixels[index + 3] as f32 / 255.0;
+        let mut pixel = [0; MAX_BYTES_PER_PIXEL];
+        let pixel = &mut pixel[..F::BYTES_PER_PIXEL];
+        F::write(pixel, self.encode(src));
 
-        let r = (r * a) + dest_r * (1.0 - a);
-        let g = (g * a) + dest_g * (1.0 - a);
-        let b = (b * a) + dest_b * (1.0 - a);
-        let a = a + dest_a;
+        for y in rect.y..rect.y + rect.h {
+            let start = y as usize * self.stride + rect.x as usize * F::BYTES_PER_PIXEL;
+            let end = start + rect.w as usize * F::BYTES_PER_PIXEL;
 
-        self.pixels[index] = (r * 255.0) as u8;
-        self.pixels[index + 1] = (g * 255.0) as u8;
-        self.pixels[index + 2] = (b * 255.0) as u8;
-        self.pixels[index + 3] = (a * 255.0) as u8;
+            for dest in self.pixels[start..end].chunks_exact_mut(F::BYTES_PER_PIXEL) {
+                dest.copy_from_slice(pixel);
+            }
+        }
     }
 
     fn size(&self) -> (i32, i32) {
-        let width = self.width;
-        let height = self.pixels.len() as i32 / (4 * width);
+        (self.width, self.height)
+    }
+
+    fn push_clip(&mut self, rect: Rect) {
+        let clip = self.clip_rect().intersect(rect);
+        self.clip_stack.push(clip);
+    }
 
-        (width, height)
+    fn pop_clip(&mut self) {
+        self.clip_stack.pop();
     }
 
-    fn clear(&mut self, color: Color) {
-        let [r, g, b, a] = color.as_rgba_f32();
+    fn clip_rect(&self) -> Rect {
+        let bounds = Rect::from_size(self.size());
 
-        for pixel in self.pixels.chunks_exact_mut(4) {
-            pixel[0] = (r * 255.0) as u8;
-            pixel[1] = (g * 255.0) as u8;
-            pixel[2] = (b * 255.0) as u8;
-            pixel[3] = (a * 255.0) as u8;
+        match self.clip_stack.last() {
+            Some(clip) => bounds.intersect(*clip),
+            None => bounds,
         }
     }
 }
+
+impl<'a, F> ReadCanvas for PixelBuffer<'a, F>
+where
+    F: PixelFormat,
+{
+    fn get(&self, x: i32, y: i32) -> Option<Color> {
+        if !Rect::from_size(self.size()).contains(x, y) {
+            return None;
+        }
+
+        let [r, g, b, a] = self.decode(F::read(self.pixel(x, y)));
+
+        Some(Color::rgba(r, g, b, a))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blend_onto(dst: [u8; 4], color: Color, alpha_mode: AlphaMode) -> [u8; 4] {
+        let mut pixels = dst;
+        let mut buffer = PixelBuffer::new(1, &mut pixels).alpha_mode(alpha_mode);
+        buffer.set(0, 0, color);
+        pixels
+    }
+
+    #[test]
+    fn opaque_source_replaces_destination() {
+        let out = blend_onto([10, 20, 30, 40], Color::WHITE, AlphaMode::Straight);
+
+        assert_eq!(out, [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn half_white_over_opaque_black_rounds() {
+        let half_white = Color::rgba(1.0, 1.0, 1.0, 0.5);
+        let out = blend_onto([0, 0, 0, 255], half_white, AlphaMode::Straight);
+
+        assert_eq!(out, [128, 128, 128, 255]);
+    }
+
+    #[test]
+    fn translucent_over_transparent_keeps_source_color() {
+        let half_white = Color::rgba(1.0, 1.0, 1.0, 0.5);
+        let out = blend_onto([0, 0, 0, 0], half_white, AlphaMode::Straight);
+
+        assert_eq!(out, [255, 255, 255, 128]);
+    }
+
+    #[test]
+    fn alpha_does_not_overflow() {
+        let half_red = Color::rgba(1.0, 0.0, 0.0, 0.5);
+        let out = blend_onto([0, 0, 255, 255], half_red, AlphaMode::Straight);
+        assert_eq!(out[3], 255);
+
+        let out = blend_onto([255, 255, 255, 255], Color::WHITE, AlphaMode::Straight);
+        assert_eq!(out[3], 255);
+    }
+
+    #[test]
+    fn translucent_over_translucent_weights_destination_alpha() {
+        let half_red = Color::rgba(1.0, 0.0, 0.0, 0.5);
+        let half_blue = [0, 0, 255, 128];
+        let out = blend_onto(half_blue, half_red, AlphaMode::Straight);
+
+        assert_eq!(out, [170, 0, 85, 192]);
+    }
+
+    #[test]
+    fn transparent_over_transparent_stays_transparent() {
+        let out = blend_onto(
+            [0, 0, 0, 0],
+            Color::rgba(1.0, 1.0, 1.0, 0.0),
+            AlphaMode::Straight,
+        );
+
+        assert_eq!(out, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn premultiplied_source_over() {
+        let half_red = Color::rgba(1.0, 0.0, 0.0, 0.5);
+        let out = blend_onto([0, 0, 255, 255], half_red, AlphaMode::Premultiplied);
+        assert_eq!(out, [128, 0, 128, 255]);
+
+        let out = blend_onto([0, 0, 0, 0], half_red, AlphaMode::Premultiplied);
+        assert_eq!(out, [128, 0, 0, 128]);
+    }
+
+    #[test]
+    fn premultiplied_reads_back_straight() {
+        let mut pixels = [128, 0, 0, 128];
+        let buffer = PixelBuffer::new(1, &mut pixels).alpha_mode(AlphaMode::Premultiplied);
+
+        assert_eq!(
+            buffer.get(0, 0),
+            Some(Color::rgba(1.0, 0.0, 0.0, 128.0 / 255.0))
+        );
+    }
+
+    #[test]
+    fn triangles_sharing_an_edge_cover_it_once() {
+        let half_white = Color::rgba(1.0, 1.0, 1.0, 0.5);
+        let mut pixels = [0; 10 * 10 * 4];
+        let mut buffer = PixelBuffer::new(10, &mut pixels);
+
+        let mut shape = buffer.shape().fill(half_white);
+        shape.triangle((0, 0), (8, 0), (0, 8));
+        shape.triangle((8, 0), (8, 8), (0, 8));
+
+        for y in 0..10 {
+            for x in 0..10 {
+                let alpha = pixels[(y * 10 + x) * 4 + 3];
+                let expected = if x < 8 && y < 8 { 128 } else { 0 };
+
+                assert_eq!(alpha, expected, "at ({x}, {y})");
+            }
+        }
+    }
+
+    #[test]
+    fn ellipse_far_larger_than_canvas_covers_it() {
+        let mut pixels = [0; 10 * 10 * 4];
+        let mut buffer = PixelBuffer::new(10, &mut pixels);
+        buffer.shape().ellipse(5, 5, 50000, 50000);
+
+        assert!(pixels.chunks_exact(4).all(|pixel| pixel == [255; 4]));
+    }
+
+    #[test]
+    fn pie_far_larger_than_canvas_fills_its_slice() {
+        let mut pixels = [0; 10 * 10 * 4];
+        let mut buffer = PixelBuffer::new(10, &mut pixels);
+        buffer.shape().pie(5, 5, 50000, 50000, 0.0, 1.0);
+
+        assert_eq!(buffer.get(9, 6), Some(Color::WHITE));
+        assert_eq!(buffer.get(2, 2), Some(Color::rgba(0.0, 0.0, 0.0, 0.0)));
+    }
+}
@@ -0,0 +1,175 @@
+/// Describes how a single pixel is laid out in memory.
+///
+/// Colors are exchanged as straight (non-premultiplied) `[r, g, b, a]` values in `0.0..=1.0`.
+/// Formats without an alpha channel read as opaque and drop alpha on write.
+pub trait PixelFormat {
+    /// At most `MAX_BYTES_PER_PIXEL`.
+    const BYTES_PER_PIXEL: usize;
+
+    fn read(bytes: &[u8]) -> [f32; 4];
+
+    fn write(bytes: &mut [u8], rgba: [f32; 4]);
+}
+
+/// The largest pixel a `PixelFormat` can describe, enough for four `f32` channels.
+pub const MAX_BYTES_PER_PIXEL: usize = 16;
+
+/// 8-bit red, green, blue, alpha.
+pub struct Rgba8;
+
+/// 8-bit blue, green, red, alpha, as used by many framebuffers and window surfaces.
+pub struct Bgra8;
+
+/// 8-bit red, green, blue without alpha.
+pub struct Rgb8;
+
+/// 5-bit red, 6-bit green, 5-bit blue packed into a native-endian `u16`.
+pub struct Rgb565;
+
+/// 8-bit grayscale, written as the luminance of the color.
+pub struct Gray8;
+
+/// 8-bit alpha only, e.g. for masks.
+pub struct Alpha8;
+
+/// `0x00RRGGBB` packed into a native-endian `u32`.
+pub struct Xrgb8888;
+
+impl PixelFormat for Rgba8 {
+    const BYTES_PER_PIXEL: usize = 4;
+
+    fn read(bytes: &[u8]) -> [f32; 4] {
+        [
+            from_u8(bytes[0]),
+            from_u8(bytes[1]),
+            from_u8(bytes[2]),
+            from_u8(bytes[3]),
+        ]
+    }
+
+    fn write(bytes: &mut [u8], [r, g, b, a]: [f32; 4]) {
+        bytes[0] = to_u8(r);
+        bytes[1] = to_u8(g);
+        bytes[2] = to_u8(b);
+        bytes[3] = to_u8(a);
+    }
+}
+
+impl PixelFormat for Bgra8 {
+    const BYTES_PER_PIXEL: usize = 4;
+
+    fn read(bytes: &[u8]) -> [f32; 4] {
+        [
+            from_u8(bytes[2]),
+            from_u8(bytes[1]),
+            from_u8(bytes[0]),
+            from_u8(bytes[3]),
+        ]
+    }
+
+    fn write(bytes: &mut [u8], [r, g, b, a]: [f32; 4]) {
+        bytes[0] = to_u8(b);
+        bytes[1] = to_u8(g);
+        bytes[2] = to_u8(r);
+        bytes[3] = to_u8(a);
+    }
+}
+
+impl PixelFormat for Rgb8 {
+    const BYTES_PER_PIXEL: usize = 3;
+
+    fn read(bytes: &[u8]) -> [f32; 4] {
+        [from_u8(bytes[0]), from_u8(bytes[1]), from_u8(bytes[2]), 1.0]
+    }
+
+    fn write(bytes: &mut [u8], [r, g, b, _]: [f32; 4]) {
+        bytes[0] = to_u8(r);
+        bytes[1] = to_u8(g);
+        bytes[2] = to_u8(b);
+    }
+}
+
+impl PixelFormat for Rgb565 {
+    const BYTES_PER_PIXEL: usize = 2;
+
+    fn read(bytes: &[u8]) -> [f32; 4] {
+        let pixel = u16::from_ne_bytes([bytes[0], bytes[1]]) as u32;
+
+        [
+            from_bits(pixel >> 11, 0x1f),
+            from_bits(pixel >> 5, 0x3f),
+            from_bits(pixel, 0x1f),
+            1.0,
+        ]
+    }
+
+    fn write(bytes: &mut [u8], [r, g, b, _]: [f32; 4]) {
+        let pixel = (to_bits(r, 0x1f) << 11) | (to_bits(g, 0x3f) << 5) | to_bits(b, 0x1f);
+
+        bytes.copy_from_slice(&(pixel as u16).to_ne_bytes());
+    }
+}
+
+impl PixelFormat for Gray8 {
+    const BYTES_PER_PIXEL: usize = 1;
+
+    fn read(bytes: &[u8]) -> [f32; 4] {
+        let v = from_u8(bytes[0]);
+
+        [v, v, v, 1.0]
+    }
+
+    fn write(bytes: &mut [u8], [r, g, b, _]: [f32; 4]) {
+        // Rec. 709 luma
+        bytes[0] = to_u8(0.2126 * r + 0.7152 * g + 0.0722 * b);
+    }
+}
+
+impl PixelFormat for Alpha8 {
+    const BYTES_PER_PIXEL: usize = 1;
+
+    fn read(bytes: &[u8]) -> [f32; 4] {
+        [0.0, 0.0, 0.0, from_u8(bytes[0])]
+    }
+
+    fn write(bytes: &mut [u8], [_, _, _, a]: [f32; 4]) {
+        bytes[0] = to_u8(a);
+    }
+}
+
+impl PixelFormat for Xrgb8888 {
+    const BYTES_PER_PIXEL: usize = 4;
+
+    fn read(bytes: &[u8]) -> [f32; 4] {
+        let pixel = u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+
+        [
+            from_bits(pixel >> 16, 0xff),
+            from_bits(pixel >> 8, 0xff),
+            from_bits(pixel, 0xff),
+            1.0,
+        ]
+    }
+
+    fn write(bytes: &mut [u8], [r, g, b, _]: [f32; 4]) {
+        let pixel = (to_bits(r, 0xff) << 16) | (to_bits(g, 0xff) << 8) | to_bits(b, 0xff);
+
+        bytes.copy_from_slice(&pixel.to_ne_bytes());
+    }
+}
+
+fn from_u8(v: u8) -> f32 {
+    v as f32 / 255.0
+}
+
+fn to_u8(v: f32) -> u8 {
+    (v.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn from_bits(v: u32, max: u32) -> f32 {
+    (v & max) as f32 / max as f32
+}
+
+fn to_bits(v: f32, max: u32) -> u32 {
+    (v.clamp(0.0, 1.0) * max as f32).round() as u32
+}
@@ -0,0 +1,51 @@
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub w: i32,
+    pub h: i32,
+}
+
+impl Rect {
+    pub fn new(x: i32, y: i32, w: i32, h: i32) -> Self {
+        Self { x, y, w, h }
+    }
+
+    pub fn from_size((w, h): (i32, i32)) -> Self {
+        Self::new(0, 0, w, h)
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && y >= self.y && x < self.x + self.w && y < self.y + self.h
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.w <= 0 || self.h <= 0
+    }
+
+    /// Returns the overlapping area, which is empty if the rects don't overlap.
+    pub fn intersect(&self, other: Rect) -> Rect {
+        let x1 = self.x.max(other.x);
+        let y1 = self.y.max(other.y);
+        let x2 = (self.x + self.w).min(other.x + other.w);
+        let y2 = (self.y + self.h).min(other.y + other.h);
+
+        Rect::new(x1, y1, (x2 - x1).max(0), (y2 - y1).max(0))
+    }
+
+    /// The part of this rect between `(x0, y0)` and `(x1, y1)` inclusive.
+    ///
+    /// Clamps the corners first, so corners too far apart to measure still work.
+    pub(crate) fn clamp_corners(&self, x0: i32, y0: i32, x1: i32, y1: i32) -> Rect {
+        let x0 = x0.max(self.x);
+        let y0 = y0.max(self.y);
+        let x1 = x1.min(self.x + self.w - 1);
+        let y1 = y1.min(self.y + self.h - 1);
+
+        if x1 < x0 || y1 < y0 {
+            return Rect::new(x0, y0, 0, 0);
+        }
+
+        Rect::new(x0, y0, x1 - x0 + 1, y1 - y0 + 1)
+    }
+}
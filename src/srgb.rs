@@ -0,0 +1,62 @@
+use std::sync::OnceLock;
+
+const ENCODE_STEPS: usize = 4096;
+
+/// Converts the color channels of sRGB encoded `[r, g, b, a]` into linear light. Alpha is kept as is.
+pub(crate) fn to_linear([r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    let table = decode_table();
+    let decode = |v: f32| table[(v.clamp(0.0, 1.0) * 255.0).round() as usize];
+
+    [decode(r), decode(g), decode(b), a]
+}
+
+/// The inverse of `to_linear`.
+pub(crate) fn to_srgb([r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    let table = encode_table();
+    let max = (ENCODE_STEPS - 1) as f32;
+    let encode = |v: f32| table[(v.clamp(0.0, 1.0) * max).round() as usize];
+
+    [encode(r), encode(g), encode(b), a]
+}
+
+fn decode_table() -> &'static [f32; 256] {
+    static TABLE: OnceLock<[f32; 256]> = OnceLock::new();
+
+    TABLE.get_or_init(|| {
+        let mut table = [0.0; 256];
+
+        for (i, v) in table.iter_mut().enumerate() {
+            *v = srgb_to_linear(i as f32 / 255.0);
+        }
+
+        table
+    })
+}
+
+fn encode_table() -> &'static [f32] {
+    static TABLE: OnceLock<Vec<f32>> = OnceLock::new();
+
+    TABLE.get_or_init(|| {
+        let max = (ENCODE_STEPS - 1) as f32;
+
+        (0..ENCODE_STEPS)
+            .map(|i| linear_to_srgb(i as f32 / max))
+            .collect()
+    })
+}
+
+fn srgb_to_linear(v: f32) -> f32 {
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(v: f32) -> f32 {
+    if v <= 0.0031308 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    }
+}
@@ -1,15 +1,29 @@
 use ab_glyph::{point, Font, Glyph, Point, PxScale, ScaleFont};
 
-use crate::{Canvas, Color};
+use crate::{BlendMode, Canvas, Color};
 
 pub struct TextCanvas<'a, T, F> {
     canvas: T,
     font: &'a F,
+    blend: BlendMode,
 }
 
 impl<'a, T, F> TextCanvas<'a, T, F> {
     pub fn new(canvas: T, font: &'a F) -> Self {
-        Self { canvas, font }
+        Self {
+            canvas,
+            font,
+            blend: BlendMode::SourceOver,
+        }
+    }
+
+    pub fn blend(mut self, mode: BlendMode) -> Self {
+        self.blend = mode;
+        self
+    }
+
+    pub fn set_blend(&mut self, mode: BlendMode) {
+        self.blend = mode;
     }
 }
 
@@ -35,7 +49,7 @@ where
                     let mut color = color;
                     color.set_a(v);
 
-                    self.canvas.set(x, y, color);
+                    self.canvas.blend(x, y, color, self.blend);
                 });
             }
         }